@@ -0,0 +1,33 @@
+//! Benchmark of PR solver throughput on one fixed 6x6 puzzle, using the
+//! bitboard fixed-point driver in `Board`/`solver_pr`. Only the current
+//! implementation is measured; there is no scalar-board baseline here to
+//! compare it against.
+//!
+//! Requires a `criterion` dev-dependency and a `[[bench]]` entry (plus
+//! exposing this crate's modules via a lib target) once the workspace gains
+//! a `Cargo.toml`; left here in the repo's benches/ convention so it's ready
+//! to wire up.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use slants_solver::solver_pr;
+
+// 24 repeats of "clue, then a 1-vertex gap" plus a trailing clue: 49 vertices,
+// matching a 6x6 board's 7x7 vertex grid.
+const SAMPLE_PUZZLE: (&str, usize, usize) = (
+    "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a3",
+    6,
+    6,
+);
+
+fn bench_pr_solve(c: &mut Criterion) {
+    let (givens, width, height) = SAMPLE_PUZZLE;
+    c.bench_function("solver_pr::solve 6x6", |b| {
+        b.iter(|| {
+            let _ = solver_pr::solve(black_box(givens), width, height, 10);
+        })
+    });
+}
+
+criterion_group!(benches, bench_pr_solve);
+criterion_main!(benches);
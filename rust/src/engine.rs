@@ -0,0 +1,142 @@
+//! Generic backtracking engine driven by the `Puzzle` trait.
+//!
+//! This is the search that used to live directly inside `solver_bf`:
+//! propagate to a fixed point, bail out on contradictions, pick the
+//! most-constrained undecided cell, and branch over its remaining candidate
+//! values. Any `Puzzle` implementation can reuse it.
+//!
+//! The search is plain recursive depth-first search driven by
+//! `checkpoint`/`rollback`, since those only support undoing in strict LIFO
+//! order; an iterative frontier of independently-resumable states would need
+//! to jump back to arbitrary earlier checkpoints, which the undo log can't
+//! do. Solutions are reported through `Puzzle::on_solution` as they're found,
+//! rather than collected as snapshots, for the same reason.
+
+use std::collections::HashSet;
+
+use crate::puzzle::Puzzle;
+
+/// Outcome of a generic backtracking search.
+pub struct EngineResult {
+    pub solutions_found: usize,
+    pub work_score: u32,
+    pub max_tier_used: u8,
+    pub used_branching: bool,
+    pub push_pop_score: u32,
+    /// Transposition-table hits/misses, if the puzzle implements `dedupe_key`.
+    pub tt_hits: u32,
+    pub tt_misses: u32,
+}
+
+/// Mutable search accounting threaded through the recursive `dfs` calls.
+struct SearchState {
+    max_solutions: usize,
+    solutions_found: usize,
+    work_score: u32,
+    max_tier_used: u8,
+    used_branching: bool,
+    push_pop_score: u32,
+    tt_hits: u32,
+    tt_misses: u32,
+    seen: HashSet<u64>,
+}
+
+/// Pick the undecided cell with the most constraints touching it, falling
+/// back to the first undecided cell if nothing distinguishes them.
+fn pick_most_constrained<P: Puzzle>(puzzle: &P) -> Option<P::Cell> {
+    let undecided = puzzle.undecided_cells();
+    let mut best: Option<P::Cell> = None;
+    let mut best_score = -1i32;
+    for cell in undecided {
+        let score = puzzle.constraints_touching(cell) as i32;
+        if score > best_score {
+            best_score = score;
+            best = Some(cell);
+        }
+    }
+    best
+}
+
+/// Propagate, validate, and (if still undecided) branch over the
+/// most-constrained cell, recursing into each candidate value under its own
+/// checkpoint. Stops recursing once `max_solutions` has been reached.
+fn dfs<P: Puzzle>(puzzle: &mut P, state: &mut SearchState) {
+    if state.solutions_found >= state.max_solutions {
+        return;
+    }
+
+    let (score, tier) = puzzle.propagate();
+    state.work_score += score;
+    state.max_tier_used = state.max_tier_used.max(tier);
+
+    if !puzzle.is_valid() {
+        return;
+    }
+
+    if let Some(key) = puzzle.dedupe_key() {
+        if !state.seen.insert(key) {
+            state.tt_hits += 1;
+            return;
+        }
+        state.tt_misses += 1;
+    }
+
+    if puzzle.is_solved() {
+        puzzle.on_solution();
+        state.solutions_found += 1;
+        return;
+    }
+
+    let cell = match pick_most_constrained(puzzle) {
+        Some(c) => c,
+        None => return,
+    };
+
+    let values = puzzle.candidate_values(cell);
+    if values.is_empty() {
+        return;
+    }
+
+    for value in values {
+        if state.solutions_found >= state.max_solutions {
+            break;
+        }
+        let checkpoint = puzzle.checkpoint();
+        state.push_pop_score += 1;
+        if puzzle.place(cell, value).is_ok() {
+            state.used_branching = true;
+            dfs(puzzle, state);
+        }
+        puzzle.rollback(checkpoint);
+    }
+}
+
+/// Run backtracking search on `puzzle` until `max_solutions` solutions are
+/// found or the search space is exhausted.
+pub fn solve<P: Puzzle>(puzzle: &mut P, max_solutions: usize) -> EngineResult {
+    let mut state = SearchState {
+        max_solutions,
+        solutions_found: 0,
+        work_score: 0,
+        max_tier_used: 0,
+        used_branching: false,
+        push_pop_score: 0,
+        tt_hits: 0,
+        tt_misses: 0,
+        seen: HashSet::new(),
+    };
+
+    let checkpoint = puzzle.checkpoint();
+    dfs(puzzle, &mut state);
+    puzzle.rollback(checkpoint);
+
+    EngineResult {
+        solutions_found: state.solutions_found,
+        work_score: state.work_score,
+        max_tier_used: state.max_tier_used,
+        used_branching: state.used_branching,
+        push_pop_score: state.push_pop_score,
+        tt_hits: state.tt_hits,
+        tt_misses: state.tt_misses,
+    }
+}
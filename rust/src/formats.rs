@@ -0,0 +1,317 @@
+//! Import/export of Slant puzzle formats beyond this crate's tab-separated
+//! testsuite lines.
+//!
+//! Supported formats:
+//! - `Testsuite`: `name\twidth\theight\tgivens[\tanswer[\tcomment]]` (the
+//!   format `board::parse_puzzle_line` has always understood).
+//! - `Tatham`: Simon Tatham's Portable Puzzle Collection save/description
+//!   form, `WxH:givens`.
+//! - `PuzzLink`: a puzz.link-style URL or bare path, `slant/W/H/givens`
+//!   (optionally preceded by `https://puzz.link/p?`).
+//!
+//! `write_solution` appends the solved grid as an extra `:solution` /
+//! `/solution` segment on `Tatham`/`PuzzLink` output - neither format
+//! actually has a solution slot, so this is this crate's own write-only
+//! extension (there's no matching read path, unlike `SolutionFormat`
+//! below, which has `parse_solution`); `Testsuite` already had a solution
+//! column and needs no such extension.
+//!
+//! All three share this crate's existing run-length given encoding (digits
+//! 0-4 for clues, letters `a`..`z` for a run of 1-26 clueless vertices), so
+//! autodetection only needs to recognize the surrounding punctuation.
+//!
+//! `SolutionFormat` is a separate, smaller format family below for
+//! serializing a solved board's diagonal grid rather than its givens: the
+//! crate's existing compact string, a newline-per-row grid for humans, and a
+//! run-length form (reusing the same letter-run scheme as givens, just
+//! over `.` cells instead of clueless vertices) for boards still mostly
+//! undecided. The CLI's `-sf/--solution-format` picks one to render `-v`
+//! solutions in; `parse_solution` is the inverse of `serialize_solution`
+//! for each, though nothing in the CLI currently reads a solution back in -
+//! it exists for round-tripping and for any future caller that wants to
+//! store/reload a solved grid.
+
+use crate::board::Puzzle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    Testsuite,
+    Tatham,
+    PuzzLink,
+}
+
+/// Guess the format of one input line.
+pub fn detect_format(line: &str) -> InputFormat {
+    let line = line.trim();
+    if line.contains("puzz.link") || line.starts_with("slant/") || line.contains("/p?slant") {
+        InputFormat::PuzzLink
+    } else if line.contains('\t') {
+        InputFormat::Testsuite
+    } else if line.contains(':') && line.contains('x') {
+        InputFormat::Tatham
+    } else {
+        InputFormat::Testsuite
+    }
+}
+
+/// Parse a line in any supported format, autodetecting which one.
+pub fn parse_any(line: &str) -> Option<Puzzle> {
+    match detect_format(line) {
+        InputFormat::Testsuite => crate::board::parse_puzzle_line(line),
+        InputFormat::Tatham => parse_tatham(line),
+        InputFormat::PuzzLink => parse_puzzlink(line),
+    }
+}
+
+/// Parse Simon Tatham `WxH:givens` descriptions.
+pub fn parse_tatham(line: &str) -> Option<Puzzle> {
+    let line = line.trim();
+    let (dims, givens) = line.split_once(':')?;
+    let (w, h) = dims.split_once('x')?;
+    Some(Puzzle {
+        name: "tatham".to_string(),
+        width: w.parse().ok()?,
+        height: h.parse().ok()?,
+        givens: givens.to_string(),
+        answer: None,
+        comment: None,
+    })
+}
+
+/// Serialize to Simon Tatham `WxH:givens` form.
+pub fn to_tatham(width: usize, height: usize, givens: &str) -> String {
+    format!("{}x{}:{}", width, height, givens)
+}
+
+/// Same as `to_tatham`, with the solved grid appended as an extra
+/// colon-delimited field.
+fn to_tatham_solved(width: usize, height: usize, givens: &str, solution: &str) -> String {
+    format!("{}:{}", to_tatham(width, height, givens), solution)
+}
+
+/// Parse a puzz.link-style URL or bare `slant/W/H/givens` path.
+pub fn parse_puzzlink(line: &str) -> Option<Puzzle> {
+    let line = line.trim();
+    let path = line.rsplit("p?").next().unwrap_or(line);
+    let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let parts = if parts.first() == Some(&"slant") {
+        &parts[1..]
+    } else {
+        &parts[..]
+    };
+    if parts.len() != 3 {
+        return None;
+    }
+    Some(Puzzle {
+        name: "puzzlink".to_string(),
+        width: parts[0].parse().ok()?,
+        height: parts[1].parse().ok()?,
+        givens: parts[2].to_string(),
+        answer: None,
+        comment: None,
+    })
+}
+
+/// Serialize to a puzz.link-style URL.
+pub fn to_puzzlink(width: usize, height: usize, givens: &str) -> String {
+    format!("https://puzz.link/p?slant/{}/{}/{}", width, height, givens)
+}
+
+/// Same as `to_puzzlink`, with the solved grid appended as an extra path
+/// segment.
+fn to_puzzlink_solved(width: usize, height: usize, givens: &str, solution: &str) -> String {
+    format!("{}/{}", to_puzzlink(width, height, givens), solution)
+}
+
+/// Serialize a solved puzzle in the requested output format.
+pub fn write_solution(
+    format: InputFormat,
+    puzzle: &Puzzle,
+    solution: &str,
+) -> String {
+    match format {
+        InputFormat::Testsuite => format!(
+            "{}\t{}\t{}\t{}\t{}",
+            puzzle.name, puzzle.width, puzzle.height, puzzle.givens, solution
+        ),
+        InputFormat::Tatham => to_tatham_solved(puzzle.width, puzzle.height, &puzzle.givens, solution),
+        InputFormat::PuzzLink => to_puzzlink_solved(puzzle.width, puzzle.height, &puzzle.givens, solution),
+    }
+}
+
+/// Parse a `-w/--write` option value into an `InputFormat`.
+pub fn parse_format_name(name: &str) -> Option<InputFormat> {
+    match name.to_uppercase().as_str() {
+        "TESTSUITE" => Some(InputFormat::Testsuite),
+        "TATHAM" => Some(InputFormat::Tatham),
+        "PUZZLINK" => Some(InputFormat::PuzzLink),
+        _ => None,
+    }
+}
+
+/// Format for a solved board's diagonal grid - as opposed to `InputFormat`
+/// above, which covers puzzle *givens*, not solutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolutionFormat {
+    /// This crate's existing no-separator `/`, `\`, `.` string.
+    Compact,
+    /// One row per line, for a human to read at a glance.
+    Grid,
+    /// `Compact`, but with runs of `.` collapsed using the same `a`..`z`
+    /// run-length letters `encode_givens`/`decode_givens` use - worthwhile
+    /// for a board that's still mostly undecided.
+    Rle,
+}
+
+/// Parse a `-sf/--solution-format` option value into a `SolutionFormat`.
+pub fn parse_solution_format_name(name: &str) -> Option<SolutionFormat> {
+    match name.to_uppercase().as_str() {
+        "COMPACT" => Some(SolutionFormat::Compact),
+        "GRID" => Some(SolutionFormat::Grid),
+        "RLE" => Some(SolutionFormat::Rle),
+        _ => None,
+    }
+}
+
+/// Serialize a canonical compact solution string into `format`.
+pub fn serialize_solution(format: SolutionFormat, solution: &str, width: usize) -> String {
+    match format {
+        SolutionFormat::Compact => solution.to_string(),
+        SolutionFormat::Grid => solution
+            .as_bytes()
+            .chunks(width)
+            .map(|row| String::from_utf8_lossy(row).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        SolutionFormat::Rle => encode_solution_rle(solution),
+    }
+}
+
+/// Parse a solution previously produced by `serialize_solution` for the same
+/// `format`/`width` back into the canonical compact `/`/`\`/`.`-per-cell
+/// string. Returns `None` for `Grid` input whose total cell count isn't a
+/// multiple of `width`; individual row lengths are not checked.
+pub fn parse_solution(format: SolutionFormat, text: &str, width: usize) -> Option<String> {
+    match format {
+        SolutionFormat::Compact => Some(text.to_string()),
+        SolutionFormat::Grid => {
+            let joined: String = text.lines().collect();
+            if width == 0 || joined.len() % width != 0 {
+                return None;
+            }
+            Some(joined)
+        }
+        SolutionFormat::Rle => Some(decode_solution_rle(text)),
+    }
+}
+
+/// Inverse of `encode_solution_rle`: expand `a`..`z` run-length letters back
+/// into runs of `.`, leaving `/` and `\` as literal characters.
+fn decode_solution_rle(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if c.is_ascii_lowercase() {
+            let run = (c as u8 - b'a' + 1) as usize;
+            out.push_str(&".".repeat(run));
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Collapse runs of `.` using the givens RLE's letter-run scheme, leaving
+/// `/` and `\` as literal characters.
+fn encode_solution_rle(solution: &str) -> String {
+    let mut out = String::new();
+    let mut run = 0usize;
+    for c in solution.chars() {
+        if c == '.' {
+            run += 1;
+            continue;
+        }
+        while run > 0 {
+            let chunk = run.min(26);
+            out.push((b'a' + (chunk - 1) as u8) as char);
+            run -= chunk;
+        }
+        out.push(c);
+    }
+    while run > 0 {
+        let chunk = run.min(26);
+        out.push((b'a' + (chunk - 1) as u8) as char);
+        run -= chunk;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_puzzle() -> Puzzle {
+        Puzzle {
+            name: "sample".to_string(),
+            width: 2,
+            height: 2,
+            givens: "e".to_string(),
+            answer: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn write_solution_embeds_the_solved_grid_in_every_format() {
+        let puzzle = sample_puzzle();
+        let solution = "/\\\\/";
+
+        assert!(write_solution(InputFormat::Testsuite, &puzzle, solution).contains(solution));
+        assert!(write_solution(InputFormat::Tatham, &puzzle, solution).contains(solution));
+        assert!(write_solution(InputFormat::PuzzLink, &puzzle, solution).contains(solution));
+    }
+
+    #[test]
+    fn tatham_round_trip_preserves_dimensions_and_givens() {
+        let givens = "2a3b1c";
+        let line = to_tatham(4, 5, givens);
+        let parsed = parse_tatham(&line).unwrap();
+        assert_eq!(parsed.width, 4);
+        assert_eq!(parsed.height, 5);
+        assert_eq!(parsed.givens, givens);
+    }
+
+    #[test]
+    fn puzzlink_round_trip_preserves_dimensions_and_givens() {
+        let givens = "2a3b1c";
+        let line = to_puzzlink(4, 5, givens);
+        let parsed = parse_puzzlink(&line).unwrap();
+        assert_eq!(parsed.width, 4);
+        assert_eq!(parsed.height, 5);
+        assert_eq!(parsed.givens, givens);
+    }
+
+    #[test]
+    fn solution_formats_round_trip_for_a_3x3_grid() {
+        let width = 3;
+        let solution = "/\\./\\./\\.";
+        for format in [SolutionFormat::Compact, SolutionFormat::Grid, SolutionFormat::Rle] {
+            let serialized = serialize_solution(format, solution, width);
+            let parsed = parse_solution(format, &serialized, width)
+                .unwrap_or_else(|| panic!("{:?} failed to parse its own serialization", format));
+            assert_eq!(parsed, solution, "{:?} did not round-trip", format);
+        }
+    }
+
+    #[test]
+    fn rle_collapses_dot_runs_but_leaves_orientations_alone() {
+        assert_eq!(encode_solution_rle("..../\\..."), "d/\\c");
+    }
+
+    #[test]
+    fn serialize_solution_dispatches_on_format() {
+        let solution = "/\\..";
+        assert_eq!(serialize_solution(SolutionFormat::Compact, solution, 2), solution);
+        assert_eq!(serialize_solution(SolutionFormat::Grid, solution, 2), "/\\\n..");
+        assert_eq!(serialize_solution(SolutionFormat::Rle, solution, 2), "/\\b");
+    }
+}
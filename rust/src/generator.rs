@@ -0,0 +1,235 @@
+//! Slants puzzle generator.
+//!
+//! Builds a random, loop-free full grid by placing a random orientation in
+//! every cell (falling back to the other orientation, then backtracking, on
+//! a loop), derives a fully-clued puzzle from it via `count_touches`, then
+//! greedily strips clues while `solver_bf::count_solutions` confirms the
+//! puzzle still has exactly one solution. The production-rule solver grades
+//! the result so `generate` can retry until it lands on the requested
+//! difficulty. `generate_for_tier` takes a more direct route to a similar
+//! end: it bounds every clue removal by a target rule tier as it strips,
+//! rather than grading a whole finished grid and retrying.
+
+use crate::board::{encode_givens, Board, Puzzle, SolveResult, BACKSLASH, SLASH};
+use crate::rules::{get_pr_rules, run_to_fixed_point};
+use crate::solver_bf;
+use crate::solver_pr;
+
+/// splitmix64, seeded per call to `generate` so puzzles are reproducible
+/// from a seed; same generator as the one that seeds Zobrist keys in `board`.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Fill every cell of a blank `width x height` board with a random
+/// loop-free orientation, backtracking when neither orientation at a cell
+/// avoids a loop.
+fn fill_random_solution(width: usize, height: usize, rng: &mut Rng) -> Board {
+    let blank_clues = vec![None; (width + 1) * (height + 1)];
+    let mut board = Board::new(width, height, &encode_givens(&blank_clues))
+        .expect("blank givens always decode to the right vertex count");
+
+    let mut cells: Vec<(usize, usize)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .collect();
+    rng.shuffle(&mut cells);
+
+    fill_cells(&mut board, &cells, 0, rng);
+    board
+}
+
+fn fill_cells(board: &mut Board, cells: &[(usize, usize)], idx: usize, rng: &mut Rng) -> bool {
+    if idx == cells.len() {
+        return true;
+    }
+    let (cx, cy) = cells[idx];
+
+    let mut values = [SLASH, BACKSLASH];
+    if rng.below(2) == 1 {
+        values.swap(0, 1);
+    }
+
+    for value in values {
+        if board.would_form_loop(cx, cy, value) {
+            continue;
+        }
+        let checkpoint = board.push_checkpoint();
+        if board.place_value(cx, cy, value).is_ok() && fill_cells(board, cells, idx + 1, rng) {
+            return true;
+        }
+        board.rollback_to(checkpoint);
+    }
+
+    false
+}
+
+/// Derive every vertex's clue from a fully-assigned board.
+fn full_clues(board: &Board) -> Vec<Option<u8>> {
+    let mut clues = Vec::with_capacity((board.width + 1) * (board.height + 1));
+    for vy in 0..=board.height {
+        for vx in 0..=board.width {
+            let (touches, _unknown) = board.count_touches(vx, vy);
+            clues.push(Some(touches));
+        }
+    }
+    clues
+}
+
+/// Greedily remove clues in random order, keeping each removal only if the
+/// puzzle still has exactly one solution.
+fn strip_clues(width: usize, height: usize, mut clues: Vec<Option<u8>>, rng: &mut Rng) -> String {
+    let mut order: Vec<usize> = (0..clues.len()).collect();
+    rng.shuffle(&mut order);
+
+    for idx in order {
+        let saved = clues[idx];
+        clues[idx] = None;
+        let givens = encode_givens(&clues);
+        let unique = matches!(
+            solver_bf::count_solutions(&givens, width, height, 2),
+            Ok(1)
+        );
+        if !unique {
+            clues[idx] = saved;
+        }
+    }
+
+    encode_givens(&clues)
+}
+
+/// Generate a uniquely-solvable Slants puzzle of the given size with a
+/// difficulty grade matching `target_difficulty` ("Easy", "Medium", "Hard",
+/// or "Expert"), retrying with a fresh random grid (drawn from the same
+/// seeded RNG, not reseeded) up to `max_attempts` times.
+pub fn generate(
+    width: usize,
+    height: usize,
+    target_difficulty: &str,
+    seed: u64,
+    max_attempts: usize,
+) -> Result<Puzzle, String> {
+    let mut rng = Rng::new(seed);
+
+    for _ in 0..max_attempts {
+        let board = fill_random_solution(width, height, &mut rng);
+        let answer = board.to_solution_string();
+        let clues = full_clues(&board);
+        let givens = strip_clues(width, height, clues, &mut rng);
+
+        let result = solver_pr::solve(&givens, width, height, 10)?;
+        if result.status != "solved" {
+            continue;
+        }
+        if result.difficulty == target_difficulty {
+            return Ok(Puzzle {
+                name: format!("generated-{}x{}", width, height),
+                width,
+                height,
+                givens,
+                answer: Some(answer),
+                comment: None,
+            });
+        }
+    }
+
+    Err(format!(
+        "could not generate a {}x{} {} puzzle in {} attempts",
+        width, height, target_difficulty, max_attempts
+    ))
+}
+
+/// Whether the production rules alone (no search fallback) finish `givens`
+/// without ever needing a tier above `max_tier`. Deliberately bypasses
+/// `solver_pr::solve`, which since its search fallback will report
+/// `"solved"` on nearly anything - this needs to know what *logic alone*,
+/// bounded by tier, can do.
+fn rules_alone_solve_within_tier(givens: &str, width: usize, height: usize, max_tier: u8) -> bool {
+    let mut board = match Board::new(width, height, givens) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    run_to_fixed_point(&mut board, &get_pr_rules(), max_tier);
+    board.is_solved() && board.is_valid_solution()
+}
+
+/// Generate a uniquely-solvable Slants puzzle whose production-rule solve
+/// never needs a rule tier above `target_max_tier`. Strips clues from a
+/// full random grid one at a time, in random order, keeping each removal
+/// only when the puzzle both stays uniquely solvable
+/// (`solver_pr::count_solutions`) and stays finishable by rules alone
+/// within the tier bound (`rules_alone_solve_within_tier`) - rejecting it
+/// otherwise, rather than `generate`'s retry-a-whole-grid-from-scratch
+/// approach. Returns the puzzle alongside the grading metadata the bound
+/// was checked against.
+pub fn generate_for_tier(
+    width: usize,
+    height: usize,
+    target_max_tier: u8,
+    seed: u64,
+) -> Result<(Puzzle, SolveResult), String> {
+    let mut rng = Rng::new(seed);
+    let board = fill_random_solution(width, height, &mut rng);
+    let answer = board.to_solution_string();
+    let mut clues = full_clues(&board);
+
+    let mut order: Vec<usize> = (0..clues.len()).collect();
+    rng.shuffle(&mut order);
+
+    for idx in order {
+        let saved = clues[idx];
+        clues[idx] = None;
+        let givens = encode_givens(&clues);
+
+        let unique = matches!(solver_pr::count_solutions(&givens, width, height, 2), Ok(1));
+        let within_tier = unique && rules_alone_solve_within_tier(&givens, width, height, target_max_tier);
+
+        if !within_tier {
+            clues[idx] = saved;
+        }
+    }
+
+    let givens = encode_givens(&clues);
+    let result = solver_pr::solve(&givens, width, height, 10)?;
+    if result.max_tier_used > target_max_tier {
+        return Err(format!(
+            "could not strip a {}x{} puzzle down to tier {} from seed {}",
+            width, height, target_max_tier, seed
+        ));
+    }
+
+    Ok((
+        Puzzle {
+            name: format!("generated-{}x{}", width, height),
+            width,
+            height,
+            givens,
+            answer: Some(answer),
+            comment: None,
+        },
+        result,
+    ))
+}
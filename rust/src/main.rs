@@ -5,30 +5,56 @@
 //! Reads puzzles from a testsuite file and attempts to solve them.
 
 mod board;
+mod engine;
+mod formats;
+mod generator;
+mod puzzle;
+mod rule_set;
 mod rules;
 mod solver_bf;
 mod solver_pr;
+mod solver_sat;
+mod solver_search;
+mod solver_tutor;
 
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::time::Instant;
 
-use board::parse_puzzle_line;
+use board::{BACKSLASH, SLASH};
+use formats::{parse_any, parse_format_name, write_solution, InputFormat};
 
 fn print_usage() {
     eprintln!("Usage: slants_solver [OPTIONS] <INPUT_FILE>");
     eprintln!();
     eprintln!("Options:");
-    eprintln!("  -s, --solver <PR|BF>  Solver to use (default: PR)");
+    eprintln!("  -s, --solver <PR|BF|SAT|TUTOR|RULESET>  Solver to use (default: PR)");
+    eprintln!("                        TUTOR also prints its per-cell deduction trail with -v");
+    eprintln!("                        RULESET requires --rule-set");
+    eprintln!("  --rule-set <FILE>     JSON RuleSet (see rule_set::RuleSet::to_json) for -s RULESET");
+    eprintln!("  --dump-rule-set <FILE>  Write the default RuleSet (every PR rule, enabled) as JSON and exit");
     eprintln!("  -n <N>                Maximum number of puzzles to test");
     eprintln!("  -ofst <N>             Puzzle number to start at (1-based, default: 1)");
     eprintln!("  -f, --filter <STR>    Filter puzzles by partial name match");
     eprintln!("  -v, --verbose         Output testsuite-compatible lines with work scores");
     eprintln!("  -mt, --max_tier <N>   Maximum rule tier to use (1, 2, or 3, default: 10 = all)");
+    eprintln!("  -w, --write <FMT>     Output format for -v solutions: TESTSUITE|TATHAM|PUZZLINK");
+    eprintln!("  -sf, --solution-format <FMT>  Solution cell encoding for the above: COMPACT|GRID|RLE (default: COMPACT)");
+    eprintln!("  -rt, --rule-tally     With -v, also print board::grade's fuller difficulty report (tier, scores, rules used)");
+    eprintln!("  --generate <WxH>:<DIFFICULTY>[:<SEED>[:<MAX_ATTEMPTS>]]");
+    eprintln!("                        Generate a puzzle of the given difficulty (Easy|Medium|Hard|Expert) and print it, instead of reading <INPUT_FILE>");
+    eprintln!("  --generate-tier <WxH>:<MAX_TIER>[:<SEED>]");
+    eprintln!("                        Generate a puzzle whose PR solve never needs a rule tier above <MAX_TIER> and print it");
     eprintln!("  -h, --help            Show this help message");
 }
 
+/// Parse a `WxH` dimension pair, as used by `--generate`/`--generate-tier`.
+fn parse_dims(s: &str) -> Option<(usize, usize)> {
+    let (w, h) = s.split_once('x')?;
+    Some((w.parse().ok()?, h.parse().ok()?))
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
@@ -45,6 +71,10 @@ fn main() {
     let mut filter: Option<String> = None;
     let mut verbose = false;
     let mut max_tier = 10u8;
+    let mut write_format: Option<InputFormat> = None;
+    let mut solution_format = formats::SolutionFormat::Compact;
+    let mut rule_set_path: Option<String> = None;
+    let mut rule_tally = false;
 
     let mut i = 1;
     while i < args.len() {
@@ -80,12 +110,118 @@ fn main() {
             "-v" | "--verbose" => {
                 verbose = true;
             }
+            "-rt" | "--rule-tally" => {
+                rule_tally = true;
+            }
             "-mt" | "--max_tier" => {
                 i += 1;
                 if i < args.len() {
                     max_tier = args[i].parse().unwrap_or(10);
                 }
             }
+            "-w" | "--write" => {
+                i += 1;
+                if i < args.len() {
+                    write_format = parse_format_name(&args[i]);
+                    if write_format.is_none() {
+                        eprintln!("Unknown output format: {}", args[i]);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "-sf" | "--solution-format" => {
+                i += 1;
+                if i < args.len() {
+                    solution_format = match formats::parse_solution_format_name(&args[i]) {
+                        Some(fmt) => fmt,
+                        None => {
+                            eprintln!("Unknown solution format: {}", args[i]);
+                            std::process::exit(1);
+                        }
+                    };
+                }
+            }
+            "--generate" => {
+                i += 1;
+                let spec = args.get(i).cloned().unwrap_or_else(|| {
+                    eprintln!("--generate requires <WxH>:<DIFFICULTY>[:<SEED>[:<MAX_ATTEMPTS>]]");
+                    std::process::exit(1);
+                });
+                let parts: Vec<&str> = spec.split(':').collect();
+                let (width, height) = parts
+                    .first()
+                    .and_then(|dims| parse_dims(dims))
+                    .unwrap_or_else(|| {
+                        eprintln!("Invalid dimensions in --generate spec: {}", spec);
+                        std::process::exit(1);
+                    });
+                let difficulty = parts.get(1).copied().unwrap_or("Medium");
+                let seed: u64 = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+                let max_attempts: usize = parts.get(3).and_then(|s| s.parse().ok()).unwrap_or(200);
+
+                match generator::generate(width, height, difficulty, seed, max_attempts) {
+                    Ok(puzzle) => {
+                        let answer = puzzle.answer.clone().unwrap_or_default();
+                        println!("{}", write_solution(InputFormat::Testsuite, &puzzle, &answer));
+                    }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
+            "--generate-tier" => {
+                i += 1;
+                let spec = args.get(i).cloned().unwrap_or_else(|| {
+                    eprintln!("--generate-tier requires <WxH>:<MAX_TIER>[:<SEED>]");
+                    std::process::exit(1);
+                });
+                let parts: Vec<&str> = spec.split(':').collect();
+                let (width, height) = parts
+                    .first()
+                    .and_then(|dims| parse_dims(dims))
+                    .unwrap_or_else(|| {
+                        eprintln!("Invalid dimensions in --generate-tier spec: {}", spec);
+                        std::process::exit(1);
+                    });
+                let target_max_tier: u8 = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(2);
+                let seed: u64 = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+                match generator::generate_for_tier(width, height, target_max_tier, seed) {
+                    Ok((puzzle, result)) => {
+                        let answer = puzzle.answer.clone().unwrap_or_default();
+                        println!(
+                            "{}\t# max_tier_used={} difficulty={}",
+                            write_solution(InputFormat::Testsuite, &puzzle, &answer),
+                            result.max_tier_used,
+                            result.difficulty
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
+            "--rule-set" => {
+                i += 1;
+                if i < args.len() {
+                    rule_set_path = Some(args[i].clone());
+                }
+            }
+            "--dump-rule-set" => {
+                i += 1;
+                if i < args.len() {
+                    let json = rule_set::RuleSet::from_pr_rules().to_json();
+                    if let Err(e) = std::fs::write(&args[i], json) {
+                        eprintln!("Error writing rule set to {}: {}", args[i], e);
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
             arg if !arg.starts_with('-') => {
                 input_file = Some(arg.to_string());
             }
@@ -124,7 +260,7 @@ fn main() {
             Ok(l) => l,
             Err(_) => continue,
         };
-        if let Some(puzzle) = parse_puzzle_line(&line) {
+        if let Some(puzzle) = parse_any(&line) {
             puzzles.push(puzzle);
         }
     }
@@ -156,6 +292,21 @@ fn main() {
         puzzles.truncate(n);
     }
 
+    let rule_set = rule_set_path.map(|path| {
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Error reading rule set {}: {}", path, e);
+            std::process::exit(1);
+        });
+        rule_set::RuleSet::from_json(&text).unwrap_or_else(|| {
+            eprintln!("Could not parse rule set {}", path);
+            std::process::exit(1);
+        })
+    });
+    if solver == "RULESET" && rule_set.is_none() {
+        eprintln!("-s RULESET requires --rule-set <FILE>");
+        std::process::exit(1);
+    }
+
     // Solve puzzles
     let total_puzzles = puzzles.len();
     let mut solved_count = 0usize;
@@ -163,12 +314,34 @@ fn main() {
     let mut mult_count = 0usize;
     let mut total_work_score = 0u32;
     let mut tier_counts = [0usize; 4]; // tiers 0, 1, 2, 3
+    let mut total_solution_rate = 0.0f64;
+    let mut difficulty_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
 
     let start_time = Instant::now();
 
-    for (_i, puzzle) in puzzles.iter().enumerate() {
+    for puzzle in &puzzles {
+        let mut tutor_deductions: Option<Vec<solver_tutor::Deduction>> = None;
+        let grade = if rule_tally {
+            board::Board::new(puzzle.width, puzzle.height, &puzzle.givens)
+                .ok()
+                .map(|mut b| board::grade(&mut b))
+        } else {
+            None
+        };
         let result = match solver.as_str() {
             "BF" => solver_bf::solve(&puzzle.givens, puzzle.width, puzzle.height, max_tier),
+            "SAT" => solver_sat::solve(&puzzle.givens, puzzle.width, puzzle.height, max_tier),
+            "TUTOR" => solver_tutor::solve(&puzzle.givens, puzzle.width, puzzle.height, max_tier)
+                .map(|tutor_result| {
+                    tutor_deductions = Some(tutor_result.deductions);
+                    tutor_result.result
+                }),
+            "RULESET" => rule_set::solve(
+                &puzzle.givens,
+                puzzle.width,
+                puzzle.height,
+                rule_set.as_ref().expect("checked above"),
+            ),
             _ => solver_pr::solve(&puzzle.givens, puzzle.width, puzzle.height, max_tier),
         };
 
@@ -187,6 +360,8 @@ fn main() {
         if is_solved {
             solved_count += 1;
             total_work_score += result.work_score;
+            total_solution_rate += result.solution_rate;
+            *difficulty_counts.entry(result.difficulty.clone()).or_insert(0) += 1;
             if result.max_tier_used <= 3 {
                 tier_counts[result.max_tier_used as usize] += 1;
             }
@@ -198,6 +373,8 @@ fn main() {
 
         if verbose {
             let solution_str = if is_solved { &result.solution } else { "" };
+            let formatted_solution =
+                formats::serialize_solution(solution_format, solution_str, puzzle.width);
             let mut comment_parts = Vec::new();
             if let Some(ref c) = puzzle.comment {
                 if !c.is_empty() {
@@ -205,6 +382,19 @@ fn main() {
                 }
             }
             comment_parts.push(format!("work_score={}", result.work_score));
+            comment_parts.push(format!(
+                "solution_rate={:.2} difficulty={}",
+                result.solution_rate, result.difficulty
+            ));
+            if result.tt_hits > 0 || result.tt_misses > 0 {
+                comment_parts.push(format!(
+                    "tt_hits={} tt_misses={}",
+                    result.tt_hits, result.tt_misses
+                ));
+            }
+            if result.max_weight_used > 0 {
+                comment_parts.push(format!("max_weight_used={}", result.max_weight_used));
+            }
             if !is_solved {
                 comment_parts.push(format!("status={}", result.status));
                 if unsolved_squares > 0 {
@@ -213,10 +403,43 @@ fn main() {
             }
             let comment = comment_parts.join(" ");
 
-            println!("{}\t{}\t{}\t{}\t{}\t# {}",
-                puzzle.name, puzzle.width, puzzle.height,
-                puzzle.givens, solution_str, comment
-            );
+            match write_format {
+                Some(fmt) => {
+                    println!("{}\t# {}", write_solution(fmt, puzzle, &formatted_solution), comment);
+                }
+                None => {
+                    println!("{}\t{}\t{}\t{}\t{}\t# {}",
+                        puzzle.name, puzzle.width, puzzle.height,
+                        puzzle.givens, formatted_solution, comment
+                    );
+                }
+            }
+
+            if let Some(deductions) = &tutor_deductions {
+                for d in deductions {
+                    let value_char = if d.value == SLASH { '/' } else if d.value == BACKSLASH { '\\' } else { '.' };
+                    println!(
+                        "#   ({},{})={} tier={} rule={:?}",
+                        d.cell.0, d.cell.1, value_char, d.tier, d.rule
+                    );
+                }
+            }
+
+            if let Some(d) = &grade {
+                println!(
+                    "#   grade: tier={} cumulative_score={} max_step_score={} distinct_rules_used={} logic_only_solution_rate={:.2} label={}",
+                    d.max_tier_used, d.cumulative_score, d.max_step_score,
+                    d.distinct_rules_used, d.logic_only_solution_rate, d.label
+                );
+                for (tier, name, times_applied) in &d.rule_tally {
+                    println!("#     tier={} rule={} times_applied={}", tier, name, times_applied);
+                }
+                println!(
+                    "#   propagation: clue_forced={} dead_end_forced={} equivalence_forced={} vbitmap_pruned={} other={}",
+                    d.propagation.clue_forced, d.propagation.dead_end_forced,
+                    d.propagation.equivalence_forced, d.propagation.vbitmap_pruned, d.propagation.other
+                );
+            }
         }
     }
 
@@ -257,6 +480,18 @@ fn main() {
                 })
                 .collect();
             println!("Tiers: {}", tier_parts.join(" "));
+
+            let mut difficulties: Vec<(&String, &usize)> = difficulty_counts.iter().collect();
+            difficulties.sort_by_key(|(name, _)| name.to_string());
+            let difficulty_parts: Vec<String> = difficulties
+                .iter()
+                .map(|(name, count)| format!("{}={}", name, count))
+                .collect();
+            println!("Difficulty: {}", difficulty_parts.join(" "));
+            println!(
+                "Average solution rate: {:.2}",
+                total_solution_rate / solved_count as f64
+            );
         }
         println!("Elapsed time: {:.3}s", elapsed_time);
         println!("Total work score: {}", total_work_score);
@@ -0,0 +1,66 @@
+//! Generic constraint-puzzle trait.
+//!
+//! Factors out the shape that `solver_bf`'s backtracking search actually
+//! depends on, so the same search engine (see `engine`) can drive other
+//! square-tile puzzles, not just Slants. A concrete puzzle only needs to
+//! expose its cells, the values still legal for a cell, how to commit a
+//! value, and how to checkpoint/rollback its state for backtracking.
+
+/// A puzzle that can be solved by picking values for a set of cells under
+/// constraints, with backtracking on contradictions.
+pub trait Puzzle {
+    /// Identifies one tile/cell in the puzzle (e.g. a `(x, y)` coordinate).
+    type Cell: Copy + Eq;
+    /// A value that can be assigned to a cell.
+    type Value: Copy;
+
+    /// All cells in the puzzle, decided or not.
+    fn cells(&self) -> Vec<Self::Cell>;
+
+    /// Cells that do not yet have a committed value.
+    fn undecided_cells(&self) -> Vec<Self::Cell>;
+
+    /// Values still legal for `cell` given the current state.
+    fn candidate_values(&mut self, cell: Self::Cell) -> Vec<Self::Value>;
+
+    /// Number of constraints (e.g. clued vertices) touching `cell`; used by
+    /// the engine's most-constrained-cell heuristic.
+    fn constraints_touching(&self, cell: Self::Cell) -> usize;
+
+    /// Commit `value` to `cell`. Returns `Ok(true)` if the assignment was
+    /// made, `Ok(false)` if the cell already had a value, or `Err` if the
+    /// assignment directly violates an invariant (e.g. forms a loop).
+    fn place(&mut self, cell: Self::Cell, value: Self::Value) -> Result<bool, String>;
+
+    /// Run any cheap deterministic propagation to a fixed point. Returns
+    /// whatever work/progress accounting the puzzle wants to report; the
+    /// engine does not interpret the value beyond accumulating it.
+    fn propagate(&mut self) -> (u32, u8);
+
+    /// Whether the current (possibly partial) assignment is still consistent.
+    fn is_valid(&self) -> bool;
+
+    /// Whether every cell has a committed value.
+    fn is_solved(&self) -> bool;
+
+    /// Record the current point in the puzzle's undo log. Pass the result to
+    /// `rollback` to undo every mutation made since this call.
+    fn checkpoint(&mut self) -> usize;
+
+    /// Undo mutations in reverse order until the puzzle is back to the state
+    /// it had at `checkpoint`.
+    fn rollback(&mut self, checkpoint: usize);
+
+    /// An opaque fingerprint of the current state suitable for a
+    /// transposition table, or `None` if the puzzle doesn't support one.
+    /// The engine skips re-expanding any state whose key was already seen.
+    fn dedupe_key(&self) -> Option<u64> {
+        None
+    }
+
+    /// Called by the engine each time `is_solved` is found true, so the
+    /// puzzle can record the solution in whatever form it wants. The engine
+    /// itself no longer collects solutions, since rollback is strict LIFO
+    /// and can't hold onto an arbitrary earlier state to return to later.
+    fn on_solution(&mut self) {}
+}
@@ -28,7 +28,7 @@ pub fn get_pr_rules() -> Vec<(RuleInfo, fn(&mut Board) -> bool)> {
         (RuleInfo { name: "vbitmap_propagation", score: 9, tier: 2 }, rule_vbitmap_propagation),
         (RuleInfo { name: "simon_unified", score: 9, tier: 2 }, rule_simon_unified),
         (RuleInfo { name: "trial_clue_violation", score: 10, tier: 3 }, rule_trial_clue_violation),
-        (RuleInfo { name: "one_step_lookahead", score: 15, tier: 3 }, rule_one_step_lookahead),
+        (RuleInfo { name: "double_probe", score: 15, tier: 3 }, rule_double_probe),
     ]
 }
 
@@ -446,57 +446,20 @@ pub fn rule_equivalence_classes(board: &mut Board) -> bool {
     made_progress
 }
 
-/// V-bitmap propagation.
-/// Creates a local vbitmap and iterates until convergence.
+/// Shape-constraint propagation against `Board`'s shared candidate layer.
+/// Known cell values are narrowed into `candidates` as soon as they're
+/// placed (see `Board::place_value`), so this only has to push clue-derived
+/// restrictions into the layer and read it back for equivalence forcing -
+/// no more rebuilding a from-scratch bitmap on every call.
 pub fn rule_vbitmap_propagation(board: &mut Board) -> bool {
     let mut made_progress = false;
     let h = board.height;
     let w = board.width;
 
-    // Initialize local vbitmap - all shapes initially possible
-    let mut vbitmap: Vec<Vec<u8>> = vec![vec![0xF; w]; h];
-
-    // Iterate until convergence
     let mut changed = true;
     while changed {
         changed = false;
 
-        // Apply constraints from known cell values
-        for y in 0..h {
-            for x in 0..w {
-                let s = board.get_cell_value(x, y);
-                if s == UNKNOWN {
-                    continue;
-                }
-
-                let old = vbitmap[y][x];
-                if s == SLASH {
-                    vbitmap[y][x] &= !0x5; // Can't do \/ or >
-                    if x > 0 && (vbitmap[y][x - 1] & 0x2) != 0 {
-                        vbitmap[y][x - 1] &= !0x2;
-                        changed = true;
-                    }
-                    if y > 0 && (vbitmap[y - 1][x] & 0x8) != 0 {
-                        vbitmap[y - 1][x] &= !0x8;
-                        changed = true;
-                    }
-                } else {
-                    vbitmap[y][x] &= !0xA; // Can't do /\ or <
-                    if x > 0 && (vbitmap[y][x - 1] & 0x1) != 0 {
-                        vbitmap[y][x - 1] &= !0x1;
-                        changed = true;
-                    }
-                    if y > 0 && (vbitmap[y - 1][x] & 0x4) != 0 {
-                        vbitmap[y - 1][x] &= !0x4;
-                        changed = true;
-                    }
-                }
-                if vbitmap[y][x] != old {
-                    changed = true;
-                }
-            }
-        }
-
         // Apply constraints from clue values (interior vertices only)
         for vy in 1..h {
             for vx in 1..w {
@@ -507,45 +470,45 @@ pub fn rule_vbitmap_propagation(board: &mut Board) -> bool {
 
                 if clue == 1 {
                     // 1 clue: no v-shape pointing AT it
-                    let old1 = vbitmap[vy - 1][vx - 1];
-                    let old2 = vbitmap[vy][vx - 1];
-                    let old3 = vbitmap[vy - 1][vx];
-                    vbitmap[vy - 1][vx - 1] &= !0x5;
-                    vbitmap[vy][vx - 1] &= !0x2;
-                    vbitmap[vy - 1][vx] &= !0x8;
-                    if vbitmap[vy - 1][vx - 1] != old1 || vbitmap[vy][vx - 1] != old2 || vbitmap[vy - 1][vx] != old3 {
+                    if board.candidates_clear(vx - 1, vy - 1, 0x5) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx - 1, vy, 0x2) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx, vy - 1, 0x8) {
                         changed = true;
                     }
                 } else if clue == 3 {
                     // 3 clue: no v-shape pointing AWAY from it
-                    let old1 = vbitmap[vy - 1][vx - 1];
-                    let old2 = vbitmap[vy][vx - 1];
-                    let old3 = vbitmap[vy - 1][vx];
-                    vbitmap[vy - 1][vx - 1] &= !0xA;
-                    vbitmap[vy][vx - 1] &= !0x1;
-                    vbitmap[vy - 1][vx] &= !0x4;
-                    if vbitmap[vy - 1][vx - 1] != old1 || vbitmap[vy][vx - 1] != old2 || vbitmap[vy - 1][vx] != old3 {
+                    if board.candidates_clear(vx - 1, vy - 1, 0xA) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx - 1, vy, 0x1) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx, vy - 1, 0x4) {
                         changed = true;
                     }
                 } else if clue == 2 {
                     // 2 clue: propagate restrictions across
-                    let old_tl = vbitmap[vy - 1][vx - 1];
-                    let old_bl = vbitmap[vy][vx - 1];
-                    let old_tr = vbitmap[vy - 1][vx];
-
                     // Horizontal: between top pair and bottom pair
-                    let top = vbitmap[vy - 1][vx - 1] & 0x3;
-                    let bot = vbitmap[vy][vx - 1] & 0x3;
-                    vbitmap[vy - 1][vx - 1] &= !(0x3 ^ bot);
-                    vbitmap[vy][vx - 1] &= !(0x3 ^ top);
+                    let top = board.candidates_get(vx - 1, vy - 1) & 0x3;
+                    let bot = board.candidates_get(vx - 1, vy) & 0x3;
+                    if board.candidates_clear(vx - 1, vy - 1, 0x3 & !bot) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx - 1, vy, 0x3 & !top) {
+                        changed = true;
+                    }
 
                     // Vertical: between left pair and right pair
-                    let left = vbitmap[vy - 1][vx - 1] & 0xC;
-                    let right = vbitmap[vy - 1][vx] & 0xC;
-                    vbitmap[vy - 1][vx - 1] &= !(0xC ^ right);
-                    vbitmap[vy - 1][vx] &= !(0xC ^ left);
-
-                    if vbitmap[vy - 1][vx - 1] != old_tl || vbitmap[vy][vx - 1] != old_bl || vbitmap[vy - 1][vx] != old_tr {
+                    let left = board.candidates_get(vx - 1, vy - 1) & 0xC;
+                    let right = board.candidates_get(vx, vy - 1) & 0xC;
+                    if board.candidates_clear(vx - 1, vy - 1, 0xC & !right) {
+                        changed = true;
+                    }
+                    if board.candidates_clear(vx, vy - 1, 0xC & !left) {
                         changed = true;
                     }
                 }
@@ -556,7 +519,7 @@ pub fn rule_vbitmap_propagation(board: &mut Board) -> bool {
         for y in 0..h {
             for x in 0..w {
                 // Check horizontal neighbor
-                if x + 1 < w && (vbitmap[y][x] & 0x3) == 0 {
+                if x + 1 < w && (board.candidates_get(x, y) & 0x3) == 0 {
                     if board.mark_cells_equivalent(x, y, x + 1, y) {
                         made_progress = true;
                         changed = true;
@@ -564,7 +527,7 @@ pub fn rule_vbitmap_propagation(board: &mut Board) -> bool {
                 }
 
                 // Check vertical neighbor
-                if y + 1 < h && (vbitmap[y][x] & 0xC) == 0 {
+                if y + 1 < h && (board.candidates_get(x, y) & 0xC) == 0 {
                     if board.mark_cells_equivalent(x, y, x, y + 1) {
                         made_progress = true;
                         changed = true;
@@ -577,6 +540,60 @@ pub fn rule_vbitmap_propagation(board: &mut Board) -> bool {
     made_progress
 }
 
+/// Reapply `f` to `state` until it stops reporting progress, mirroring the
+/// generic "apply until fixed point" combinator other constraint solvers
+/// build propagation loops around. Returns whether anything changed across
+/// the whole run.
+pub fn fixed_point<T>(state: &mut T, mut f: impl FnMut(&mut T) -> bool) -> bool {
+    let mut any_progress = false;
+    while f(state) {
+        any_progress = true;
+    }
+    any_progress
+}
+
+/// Run `rules` against `board` to a true fixed point: keep cycling through
+/// the rule set until neither a rule reports progress nor the shared
+/// candidate layer shrinks any further, instead of stopping the moment one
+/// pass finds nothing (which would miss candidate-only narrowing that later
+/// unlocks a rule like `rule_equivalence_classes`). Returns the accumulated
+/// work score and the highest tier used.
+pub fn run_to_fixed_point(
+    board: &mut Board,
+    rules: &[(RuleInfo, fn(&mut Board) -> bool)],
+    max_tier: u8,
+) -> (u32, u8) {
+    let mut total_work_score = 0u32;
+    let mut max_tier_used = 0u8;
+
+    fixed_point(board, |board| {
+        if board.is_solved() {
+            return false;
+        }
+
+        let before_hash = board.state_hash();
+        let before_candidates = board.candidates_popcount();
+        let mut made_progress = false;
+
+        for (info, rule_func) in rules {
+            if info.tier > max_tier {
+                continue;
+            }
+            if rule_func(board) {
+                total_work_score += info.score;
+                max_tier_used = max_tier_used.max(info.tier);
+                made_progress = true;
+                break;
+            }
+        }
+
+        (made_progress && board.state_hash() != before_hash)
+            || board.candidates_popcount() != before_candidates
+    });
+
+    (total_work_score, max_tier_used)
+}
+
 /// Unified rule mimicking Simon Tatham's solver.
 /// This implements clue completion with adjacent equivalent pair tracking,
 /// loop avoidance, dead-end avoidance, and equivalence-based filling.
@@ -816,8 +833,9 @@ pub fn rule_trial_clue_violation(board: &mut Board) -> bool {
     let unknown = board.get_unknown_cells();
 
     for (cx, cy) in unknown {
-        let mut slash_valid = !board.would_form_loop(cx, cy, SLASH);
-        let mut back_valid = !board.would_form_loop(cx, cy, BACKSLASH);
+        let state = board.get_cell(cx, cy).unwrap().state;
+        let mut slash_valid = state.can_be(SLASH) && !board.would_form_loop(cx, cy, SLASH);
+        let mut back_valid = state.can_be(BACKSLASH) && !board.would_form_loop(cx, cy, BACKSLASH);
 
         // Check clue violations for slash
         if slash_valid {
@@ -872,10 +890,15 @@ pub fn rule_trial_clue_violation(board: &mut Board) -> bool {
         }
 
         if slash_valid && !back_valid {
+            // Narrow the possibility layer before committing, so CellState
+            // and the cell's actual value are set by the same rule in the
+            // same step rather than one trailing the other.
+            board.vbitmap_clear(cx, cy, BACKSLASH);
             if board.place_value(cx, cy, SLASH).is_ok() {
                 made_progress = true;
             }
         } else if back_valid && !slash_valid {
+            board.vbitmap_clear(cx, cy, SLASH);
             if board.place_value(cx, cy, BACKSLASH).is_ok() {
                 made_progress = true;
             }
@@ -885,59 +908,75 @@ pub fn rule_trial_clue_violation(board: &mut Board) -> bool {
     made_progress
 }
 
-/// One step lookahead - check if placing a diagonal causes an adjacent cell to have no options.
-pub fn rule_one_step_lookahead(board: &mut Board) -> bool {
+/// Double-probe lookahead: for each undecided cell, hypothesize SLASH and
+/// BACKSLASH in turn, propagate tier-1/tier-2 rules to a fixed point under
+/// each, and keep whatever both branches agree on. If a hypothesis
+/// contradicts (a clue exceeded, or some other cell left with no legal
+/// orientation) the opposite is forced; otherwise any *other* cell that
+/// landed on the same orientation in both branches is forced too, since
+/// that outcome didn't depend on which way the probed cell actually goes.
+/// Strictly subsumes the single-step lookahead this replaced: that only
+/// checked immediate neighbors after one placement, this propagates fully
+/// and checks clue validity as well as dead cells.
+///
+/// Runs `double_probe_pass` to a fixed point rather than just once: forcing
+/// one cell can make a previously-inconclusive probe on another cell
+/// conclusive, so it's worth re-probing until a full pass fixes nothing.
+/// A cell for which *both* orientations contradict is left untouched here -
+/// that's the same dead end `is_valid`/`is_solved` already detect further up
+/// the call stack, so the board is reported unsolved there rather than this
+/// rule duplicating that bookkeeping.
+pub fn rule_double_probe(board: &mut Board) -> bool {
+    fixed_point(board, double_probe_pass)
+}
+
+fn double_probe_pass(board: &mut Board) -> bool {
     let mut made_progress = false;
-    let unknown = board.get_unknown_cells();
+    let bf_rules = get_bf_rules();
 
-    for (cx, cy) in &unknown {
-        let cx = *cx;
-        let cy = *cy;
+    for (cx, cy) in board.get_unknown_cells() {
+        if board.get_cell_value(cx, cy) != UNKNOWN {
+            continue; // forced by an earlier probe this pass
+        }
 
-        // Try SLASH
-        let mut slash_causes_contradiction = board.would_form_loop(cx, cy, SLASH);
+        let slash_outcome = probe_orientation(board, cx, cy, SLASH, &bf_rules);
+        let back_outcome = probe_orientation(board, cx, cy, BACKSLASH, &bf_rules);
 
-        if !slash_causes_contradiction {
-            let state = board.save_state();
-            if board.place_value(cx, cy, SLASH).is_ok() {
-                // Check if any adjacent unknown now has no valid options
-                for (ax, ay) in board.get_unknown_cells() {
-                    let s_ok = !board.would_form_loop(ax, ay, SLASH);
-                    let b_ok = !board.would_form_loop(ax, ay, BACKSLASH);
-                    if !s_ok && !b_ok {
-                        slash_causes_contradiction = true;
-                        break;
-                    }
+        match (slash_outcome, back_outcome) {
+            (None, Some(_)) => {
+                if board.place_value(cx, cy, BACKSLASH).is_ok() {
+                    made_progress = true;
                 }
             }
-            board.restore_state(&state);
-        }
-
-        // Try BACKSLASH
-        let mut back_causes_contradiction = board.would_form_loop(cx, cy, BACKSLASH);
-
-        if !back_causes_contradiction {
-            let state = board.save_state();
-            if board.place_value(cx, cy, BACKSLASH).is_ok() {
-                for (ax, ay) in board.get_unknown_cells() {
-                    let s_ok = !board.would_form_loop(ax, ay, SLASH);
-                    let b_ok = !board.would_form_loop(ax, ay, BACKSLASH);
-                    if !s_ok && !b_ok {
-                        back_causes_contradiction = true;
-                        break;
-                    }
+            (Some(_), None) => {
+                if board.place_value(cx, cy, SLASH).is_ok() {
+                    made_progress = true;
                 }
             }
-            board.restore_state(&state);
-        }
-
-        if slash_causes_contradiction && !back_causes_contradiction {
-            if board.place_value(cx, cy, BACKSLASH).is_ok() {
-                made_progress = true;
+            (Some(slash_values), Some(back_values)) => {
+                let forced: Vec<(usize, usize, u8)> = board
+                    .cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, cell)| !cell.state.is_decided())
+                    .filter_map(|(idx, cell)| {
+                        let v = slash_values[idx];
+                        if v != UNKNOWN && v == back_values[idx] {
+                            Some((cell.x, cell.y, v))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                for (ax, ay, value) in forced {
+                    if board.place_value(ax, ay, value).is_ok() {
+                        made_progress = true;
+                    }
+                }
             }
-        } else if back_causes_contradiction && !slash_causes_contradiction {
-            if board.place_value(cx, cy, SLASH).is_ok() {
-                made_progress = true;
+            (None, None) => {
+                // Both orientations contradict; an earlier rule already
+                // broke this board, nothing more this rule can force.
             }
         }
     }
@@ -945,6 +984,36 @@ pub fn rule_one_step_lookahead(board: &mut Board) -> bool {
     made_progress
 }
 
+/// Place `value` at `(cx, cy)`, propagate tier-1/tier-2 rules to a fixed
+/// point, and return every cell's resulting orientation - or `None` if the
+/// hypothesis contradicts (clue overflow or a cell left with no legal
+/// orientation). Always restores `board` to its pre-probe state.
+fn probe_orientation(
+    board: &mut Board,
+    cx: usize,
+    cy: usize,
+    value: u8,
+    rules: &[(RuleInfo, fn(&mut Board) -> bool)],
+) -> Option<Vec<u8>> {
+    if board.would_form_loop(cx, cy, value) {
+        return None;
+    }
+
+    let checkpoint = board.push_checkpoint();
+    let result = if board.place_value(cx, cy, value).is_ok() {
+        run_to_fixed_point(board, rules, 2);
+        if !board.has_contradiction() {
+            Some(board.cells.iter().map(|c| c.value()).collect())
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    board.rollback_to(checkpoint);
+    result
+}
+
 // Helper functions
 
 /// Get adjacent unknown cells for a vertex with their touch relationships.
@@ -1,9 +1,92 @@
 //! Brute Force Solver for Slants (Gokigen Naname) puzzles.
-//! Uses production rules plus stack-based backtracking.
+//! Uses production rules plus the generic backtracking `engine`.
 
-use crate::board::{Board, BoardState, SolveResult, SLASH, BACKSLASH};
+use crate::board::{grade_difficulty, Board, SolveResult, SLASH};
+use crate::engine;
+use crate::puzzle::Puzzle;
 use crate::rules::{get_bf_rules, RuleInfo};
 
+/// Adapts `Board` to the generic `Puzzle` trait so `engine::solve` can drive
+/// the search; Slants is the first puzzle type to plug into the engine.
+struct SlantsPuzzle<'a> {
+    board: Board,
+    rules: &'a [(RuleInfo, fn(&mut Board) -> bool)],
+    max_tier: u8,
+    // Fraction of cells fixed by propagation alone, captured the first time
+    // `propagate` runs (i.e. before any branching has happened).
+    logic_only_rate: Option<f64>,
+    // Solutions recorded via `on_solution`, since the engine's rollback is
+    // strict LIFO and can't hand back an arbitrary earlier board state.
+    solutions: Vec<String>,
+}
+
+impl<'a> Puzzle for SlantsPuzzle<'a> {
+    type Cell = (usize, usize);
+    type Value = u8;
+
+    fn cells(&self) -> Vec<(usize, usize)> {
+        let mut cells = Vec::with_capacity(self.board.width * self.board.height);
+        for y in 0..self.board.height {
+            for x in 0..self.board.width {
+                cells.push((x, y));
+            }
+        }
+        cells
+    }
+
+    fn undecided_cells(&self) -> Vec<(usize, usize)> {
+        self.board.get_unknown_cells()
+    }
+
+    fn candidate_values(&mut self, cell: (usize, usize)) -> Vec<u8> {
+        get_valid_values(&mut self.board, cell.0, cell.1)
+    }
+
+    fn constraints_touching(&self, cell: (usize, usize)) -> usize {
+        let (cx, cy) = cell;
+        [(cx, cy), (cx + 1, cy), (cx, cy + 1), (cx + 1, cy + 1)]
+            .iter()
+            .filter(|&&(vx, vy)| self.board.get_vertex_clue(vx, vy).is_some())
+            .count()
+    }
+
+    fn place(&mut self, cell: (usize, usize), value: u8) -> Result<bool, String> {
+        self.board.place_value(cell.0, cell.1, value)
+    }
+
+    fn propagate(&mut self) -> (u32, u8) {
+        let result = apply_rules_until_stuck(&mut self.board, self.rules, self.max_tier);
+        if self.logic_only_rate.is_none() {
+            self.logic_only_rate = Some(self.board.solution_rate());
+        }
+        result
+    }
+
+    fn is_valid(&self) -> bool {
+        self.board.is_valid()
+    }
+
+    fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    fn checkpoint(&mut self) -> usize {
+        self.board.push_checkpoint()
+    }
+
+    fn rollback(&mut self, checkpoint: usize) {
+        self.board.rollback_to(checkpoint);
+    }
+
+    fn dedupe_key(&self) -> Option<u64> {
+        Some(self.board.zobrist_hash())
+    }
+
+    fn on_solution(&mut self) {
+        self.solutions.push(self.board.to_solution_string());
+    }
+}
+
 /// Apply rules until no more progress can be made.
 fn apply_rules_until_stuck(
     board: &mut Board,
@@ -12,16 +95,13 @@ fn apply_rules_until_stuck(
 ) -> (u32, u8) {
     let mut total_work_score = 0u32;
     let mut max_tier_used = 0u8;
-    let max_iterations = 1000;
-    let mut iteration = 0;
-
-    while iteration < max_iterations {
-        iteration += 1;
 
+    loop {
         if board.is_solved() || !board.is_valid() {
             break;
         }
 
+        let before_hash = board.state_hash();
         let mut made_progress = false;
         for (info, rule_func) in rules {
             if info.tier > max_tier {
@@ -36,7 +116,7 @@ fn apply_rules_until_stuck(
             }
         }
 
-        if !made_progress {
+        if !made_progress || board.state_hash() == before_hash {
             break;
         }
     }
@@ -44,55 +124,11 @@ fn apply_rules_until_stuck(
     (total_work_score, max_tier_used)
 }
 
-/// Pick the best cell for branching.
-fn pick_best_cell(board: &Board) -> Option<(usize, usize)> {
-    let unknown_cells = board.get_unknown_cells();
-    if unknown_cells.is_empty() {
-        return None;
-    }
-
-    // Score cells by how constrained they are
-    let mut best_cell = unknown_cells[0];
-    let mut best_score = 0i32;
-
-    for (cx, cy) in unknown_cells {
-        let mut score = 0i32;
-
-        // Check all 4 corners
-        for &(vx, vy) in &[
-            (cx, cy),
-            (cx + 1, cy),
-            (cx, cy + 1),
-            (cx + 1, cy + 1),
-        ] {
-            if let Some(clue) = board.get_vertex_clue(vx, vy) {
-                let (current, unknown) = board.count_touches(vx, vy);
-                let remaining_needed = clue.saturating_sub(current);
-
-                if remaining_needed == unknown {
-                    score += 100;
-                } else if remaining_needed == 0 {
-                    score += 100;
-                } else if unknown > 0 {
-                    score += 50 / (unknown as i32);
-                }
-            }
-        }
-
-        if score > best_score {
-            best_score = score;
-            best_cell = (cx, cy);
-        }
-    }
-
-    Some(best_cell)
-}
-
 /// Get valid values for a cell.
 fn get_valid_values(board: &mut Board, cx: usize, cy: usize) -> Vec<u8> {
     let mut valid = Vec::new();
 
-    for value in [SLASH, BACKSLASH] {
+    for value in board.get_cell(cx, cy).unwrap().state.variants() {
         if board.would_form_loop(cx, cy, value) {
             continue;
         }
@@ -126,6 +162,30 @@ fn get_valid_values(board: &mut Board, cx: usize, cy: usize) -> Vec<u8> {
     valid
 }
 
+/// Count distinct solutions, stopping early once `limit` is reached. Used by
+/// the puzzle generator to check that removing a clue keeps the puzzle
+/// uniquely solvable.
+pub fn count_solutions(
+    givens_string: &str,
+    width: usize,
+    height: usize,
+    limit: usize,
+) -> Result<usize, String> {
+    let board = Board::new(width, height, givens_string)?;
+    let rules = get_bf_rules();
+
+    let mut puzzle = SlantsPuzzle {
+        board,
+        rules: &rules,
+        max_tier: 10,
+        logic_only_rate: None,
+        solutions: Vec::new(),
+    };
+
+    let result = engine::solve(&mut puzzle, limit);
+    Ok(result.solutions_found)
+}
+
 /// Solve a puzzle using brute-force backtracking.
 pub fn solve(
     givens_string: &str,
@@ -133,67 +193,28 @@ pub fn solve(
     height: usize,
     max_tier: u8,
 ) -> Result<SolveResult, String> {
-    let mut board = Board::new(width, height, givens_string)?;
+    let board = Board::new(width, height, givens_string)?;
     let rules = get_bf_rules();
 
-    let mut solutions: Vec<String> = Vec::new();
-    let mut stack: Vec<(BoardState, Option<u8>)> = vec![(board.save_state(), None)];
-    let mut total_work_score = 0u32;
-    let mut max_tier_used = 0u8;
-    let mut used_branching = false;
-    let mut push_pop_score = 0u32;
-
-    while !stack.is_empty() && solutions.len() < 2 {
-        let (state, _eliminated_value) = stack.pop().unwrap();
-        board.restore_state(&state);
-        push_pop_score += 1;
-
-        // Apply rules
-        let (work_score, tier_used) = apply_rules_until_stuck(&mut board, &rules, max_tier);
-        total_work_score += work_score;
-        max_tier_used = max_tier_used.max(tier_used);
-
-        // Check validity
-        if !board.is_valid() {
-            continue;
-        }
-
-        // Check if solved
-        if board.is_solved() {
-            if board.is_valid_solution() {
-                solutions.push(board.to_solution_string());
-                continue;
-            } else {
-                continue;
-            }
-        }
-
-        // Choose cell for branching
-        let (cx, cy) = match pick_best_cell(&board) {
-            Some(cell) => cell,
-            None => continue,
-        };
+    let mut puzzle = SlantsPuzzle {
+        board,
+        rules: &rules,
+        max_tier,
+        logic_only_rate: None,
+        solutions: Vec::new(),
+    };
 
-        // Get valid values
-        let valid_values = get_valid_values(&mut board, cx, cy);
-        if valid_values.is_empty() {
-            continue;
-        }
+    let result = engine::solve(&mut puzzle, 2);
 
-        // Push states for each valid value
-        let saved_state = board.save_state();
-        for value in valid_values.iter().rev() {
-            board.restore_state(&saved_state);
-            if board.place_value(cx, cy, *value).is_ok() {
-                stack.push((board.save_state(), Some(*value)));
-                push_pop_score += 1;
-                used_branching = true;
-            }
-        }
-        board.restore_state(&saved_state);
+    let mut total_work_score = result.work_score;
+    let mut max_tier_used = result.max_tier_used;
+    total_work_score += result.push_pop_score * 2;
+    if result.used_branching {
+        max_tier_used = 3;
     }
 
-    // Determine status
+    let solutions = puzzle.solutions.clone();
+
     let status = if solutions.len() >= 2 {
         "mult".to_string()
     } else if solutions.len() == 1 {
@@ -205,19 +226,27 @@ pub fn solve(
     let solution = if solutions.len() == 1 {
         solutions[0].clone()
     } else {
-        board.to_solution_string()
+        puzzle.board.to_solution_string()
     };
 
-    total_work_score += push_pop_score * 2;
-
-    if used_branching {
-        max_tier_used = 3;
-    }
+    let solution_rate = puzzle.logic_only_rate.unwrap_or(0.0);
+    let branch_count = if result.used_branching {
+        result.push_pop_score.saturating_sub(1)
+    } else {
+        0
+    };
 
     Ok(SolveResult {
         status,
         solution,
         work_score: total_work_score,
         max_tier_used,
+        tt_hits: result.tt_hits,
+        tt_misses: result.tt_misses,
+        solution_rate,
+        branch_count,
+        difficulty: grade_difficulty(max_tier_used, branch_count, solution_rate),
+        guesses_used: branch_count,
+        max_weight_used: 0,
     })
 }
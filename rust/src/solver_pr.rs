@@ -1,9 +1,15 @@
 //! Production Rule Solver for Slants (Gokigen Naname) puzzles.
 
-use crate::board::{Board, SolveResult};
-use crate::rules::get_pr_rules;
+use crate::board::{grade_difficulty, Board, SolveResult};
+use crate::rules::{get_pr_rules, run_to_fixed_point};
+use crate::solver_search::{count_solutions as search_count_solutions, solve_with_search_depth};
 
-/// Solve a puzzle using production rules.
+/// Solve a puzzle using production rules, falling back to the rule-pruned
+/// backtracking search in `solver_search` if the rules alone reach a
+/// fixpoint without finishing - many Slants grids need a single
+/// bifurcation beyond what pure logic can settle. The fallback checks for a
+/// second solution before reporting success, so a puzzle the rules can't
+/// pin down to one answer is reported `"mult"` rather than `"solved"`.
 pub fn solve(
     givens_string: &str,
     width: usize,
@@ -13,54 +19,73 @@ pub fn solve(
     let mut board = Board::new(width, height, givens_string)?;
     let rules = get_pr_rules();
 
-    let max_iterations = 1000;
-    let mut iteration = 0;
-    let mut total_work_score = 0u32;
-    let mut max_tier_used = 0u8;
+    let (total_work_score, max_tier_used) = run_to_fixed_point(&mut board, &rules, max_tier);
 
-    while iteration < max_iterations {
-        iteration += 1;
-
-        // Check if solved
-        if board.is_solved() {
-            let status = if board.is_valid_solution() {
-                "solved"
-            } else {
-                "unsolved"
-            };
-
-            return Ok(SolveResult {
-                status: status.to_string(),
-                solution: board.to_solution_string(),
-                work_score: total_work_score,
-                max_tier_used,
-            });
-        }
-
-        // Try each rule in order
-        let mut made_progress = false;
-        for (info, rule_func) in &rules {
-            if info.tier > max_tier {
-                continue;
-            }
-
-            if rule_func(&mut board) {
-                total_work_score += info.score;
-                max_tier_used = max_tier_used.max(info.tier);
-                made_progress = true;
-                break;
-            }
+    let solution_rate = board.solution_rate();
+    let mut guesses_used = 0u32;
+    let (status, solution) = if board.is_solved() {
+        if board.is_valid_solution() {
+            ("solved", board.to_solution_string())
+        } else {
+            ("unsolved", board.to_solution_string())
         }
-
-        if !made_progress {
-            break;
-        }
-    }
+    } else if search_count_solutions(&mut board, 2) >= 2 {
+        // Rules stalled and the search fallback found more than one way to
+        // finish - report `"mult"` rather than quietly handing back
+        // whichever one it happened to find first, same as `solver_bf::solve`.
+        ("mult", board.to_solution_string())
+    } else if let Some((solution, guesses)) = solve_with_search_depth(&mut board) {
+        guesses_used = guesses;
+        ("solved", solution)
+    } else {
+        ("unsolved", board.to_solution_string())
+    };
 
     Ok(SolveResult {
-        status: "unsolved".to_string(),
-        solution: board.to_solution_string(),
+        status: status.to_string(),
+        solution,
         work_score: total_work_score,
         max_tier_used,
+        tt_hits: 0,
+        tt_misses: 0,
+        solution_rate,
+        branch_count: guesses_used,
+        difficulty: grade_difficulty(max_tier_used, guesses_used, solution_rate),
+        guesses_used,
+        max_weight_used: 0,
     })
 }
+
+/// Parse a puzzle and report how many valid solutions it admits: run
+/// production rules first, then exhaustively search whatever they leave
+/// undecided, stopping as soon as `limit` distinct solutions are found
+/// (pass 2 to confirm uniqueness without counting further). A well-formed
+/// puzzle should return exactly 1. `generator::generate_for_tier` is the
+/// main caller, confirming each clue it strips still leaves the puzzle
+/// uniquely solvable.
+pub fn count_solutions(
+    givens_string: &str,
+    width: usize,
+    height: usize,
+    limit: usize,
+) -> Result<usize, String> {
+    let mut board = Board::new(width, height, givens_string)?;
+    let rules = get_pr_rules();
+    run_to_fixed_point(&mut board, &rules, 10);
+    Ok(search_count_solutions(&mut board, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_mult_instead_of_the_first_solution_found() {
+        // A 1x1 board with no clues ("d" = a run of 4 clueless vertices)
+        // has two equally valid solutions (`/` and `\`) that the rules
+        // can't break the tie between, so the search fallback must check
+        // for a second solution before reporting success.
+        let result = solve("d", 1, 1, 10).unwrap();
+        assert_eq!(result.status, "mult");
+    }
+}
@@ -0,0 +1,632 @@
+//! SAT-based solver for Slants (Gokigen Naname) puzzles.
+//!
+//! Each cell is encoded as one boolean variable (true = BACKSLASH, false = SLASH).
+//! Clues become exactly-k-of-n cardinality constraints over the cells touching
+//! each vertex, encoded with a sequential-counter encoding. The loop-free rule
+//! is not compact to state up front, so it is enforced lazily: solve the CNF,
+//! trace the resulting diagonals with union-find, and if a closed cycle is
+//! found, add a clause blocking that exact combination of orientations and
+//! re-solve.
+
+use crate::board::{Board, SolveResult, BACKSLASH, SLASH};
+
+/// A literal is a 1-based variable index; negative means negated.
+type Lit = i32;
+
+/// A clause is a disjunction of literals.
+#[derive(Clone)]
+struct Clause {
+    lits: Vec<Lit>,
+}
+
+/// Minimal CDCL core: watched literals, unit propagation, conflict-driven
+/// backjumping, and periodic restarts.
+struct Cdcl {
+    num_vars: usize,
+    clauses: Vec<Clause>,
+    // assignment[v] for 1-based var v: None = unassigned, Some(bool) = value
+    assignment: Vec<Option<bool>>,
+    level: Vec<i32>,
+    reason: Vec<Option<usize>>, // clause index that forced this var, if any
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    // watches[lit_index] -> clause indices watching that literal
+    watches: Vec<Vec<usize>>,
+    conflicts_since_restart: usize,
+    restart_threshold: usize,
+    // Set once a clause is found false under every possible assignment the
+    // search could still reach - an empty clause, or a unit clause whose
+    // literal is already assigned the opposite way at level 0. `solve`
+    // checks this directly instead of relying on the decision loop to
+    // rediscover the same conflict every restart.
+    unsat: bool,
+}
+
+fn lit_index(lit: Lit) -> usize {
+    // Map +v -> 2v, -v -> 2v+1 so each literal has a distinct watch list slot.
+    if lit > 0 {
+        (lit as usize) * 2
+    } else {
+        (-lit as usize) * 2 + 1
+    }
+}
+
+impl Cdcl {
+    fn new(num_vars: usize) -> Self {
+        Cdcl {
+            num_vars,
+            clauses: Vec::new(),
+            assignment: vec![None; num_vars + 1],
+            level: vec![-1; num_vars + 1],
+            reason: vec![None; num_vars + 1],
+            trail: Vec::new(),
+            trail_lim: Vec::new(),
+            watches: vec![Vec::new(); (num_vars + 1) * 2 + 2],
+            conflicts_since_restart: 0,
+            restart_threshold: 64,
+            unsat: false,
+        }
+    }
+
+    /// Grow `assignment`/`level`/`reason`/`watches` to cover variable
+    /// `min_var`, if they don't already. Must run before any `add_clause`
+    /// referencing a variable beyond the table sizes `new` allocated -
+    /// `encode_exactly_k`'s auxiliary counter variables need this every
+    /// time they push `next_var` past what's currently sized.
+    fn ensure_capacity(&mut self, min_var: usize) {
+        if min_var <= self.num_vars {
+            return;
+        }
+        self.num_vars = min_var;
+        self.assignment.resize(self.num_vars + 1, None);
+        self.level.resize(self.num_vars + 1, -1);
+        self.reason.resize(self.num_vars + 1, None);
+        self.watches.resize((self.num_vars + 1) * 2 + 2, Vec::new());
+    }
+
+    fn add_clause(&mut self, lits: Vec<Lit>) {
+        if lits.is_empty() {
+            // Unsatisfiable empty clause; record it so solve() fails fast.
+            self.unsat = true;
+            self.clauses.push(Clause { lits });
+            return;
+        }
+        let idx = self.clauses.len();
+        self.watches[lit_index(-lits[0])].push(idx);
+        if lits.len() > 1 {
+            self.watches[lit_index(-lits[1])].push(idx);
+        } else {
+            // A unit clause's literal is forced outright, but nothing ever
+            // assigns its watched literal to trigger that through
+            // `propagate`'s normal watched-literal mechanism - the decision
+            // heuristic would otherwise never learn about it and could pick
+            // the opposite value forever. Enqueue it immediately instead.
+            match self.value_of(lits[0]) {
+                Some(true) => {}
+                Some(false) => self.unsat = true,
+                None => self.enqueue(lits[0], Some(idx)),
+            }
+        }
+        self.clauses.push(Clause { lits });
+    }
+
+    fn value_of(&self, lit: Lit) -> Option<bool> {
+        let v = lit.unsigned_abs() as usize;
+        self.assignment[v].map(|val| if lit > 0 { val } else { !val })
+    }
+
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let v = lit.unsigned_abs() as usize;
+        self.assignment[v] = Some(lit > 0);
+        self.level[v] = self.trail_lim.len() as i32;
+        self.reason[v] = reason;
+        self.trail.push(lit);
+    }
+
+    fn current_level(&self) -> i32 {
+        self.trail_lim.len() as i32
+    }
+
+    fn push_level(&mut self) {
+        self.trail_lim.push(self.trail.len());
+    }
+
+    /// Unit-propagate; returns the index of a conflicting clause, if any.
+    fn propagate(&mut self) -> Option<usize> {
+        let mut qhead = if self.trail_lim.is_empty() {
+            0
+        } else {
+            self.trail_lim[self.trail_lim.len() - 1]
+        };
+        // Re-scan from the start of this decision level each call; cheap enough
+        // at the puzzle sizes this solver targets.
+        qhead = qhead.min(self.trail.len());
+        while qhead < self.trail.len() {
+            let p = self.trail[qhead];
+            qhead += 1;
+            let watchers = self.watches[lit_index(p)].clone();
+            for &ci in &watchers {
+                let clause = self.clauses[ci].clone();
+                if clause.lits.is_empty() {
+                    return Some(ci);
+                }
+                // Find an unfalsified literal to watch, or propagate/conflict.
+                let mut unassigned: Option<Lit> = None;
+                let mut satisfied = false;
+                let mut false_count = 0;
+                for &l in &clause.lits {
+                    match self.value_of(l) {
+                        Some(true) => {
+                            satisfied = true;
+                            break;
+                        }
+                        Some(false) => false_count += 1,
+                        None => {
+                            if unassigned.is_none() {
+                                unassigned = Some(l); // keep first
+                            }
+                        }
+                    }
+                }
+                if satisfied {
+                    continue;
+                }
+                if false_count == clause.lits.len() {
+                    return Some(ci);
+                }
+                if false_count == clause.lits.len() - 1 {
+                    if let Some(unit) = unassigned {
+                        if self.value_of(unit).is_none() {
+                            self.enqueue(unit, Some(ci));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Analyze a conflict and return the backjump level plus the learned clause.
+    ///
+    /// Every literal in `confl_clause` is currently false. Walk that set
+    /// resolving away each variable that was *propagated* at the current
+    /// decision level - substituting it with the other (already-false)
+    /// literals of the antecedent clause that forced it - and keep
+    /// everything else (literals from earlier levels, plus the current
+    /// level's lone decision literal, which has no antecedent to resolve
+    /// against). Since only one variable per level is ever a decision, this
+    /// always converges to a clause containing exactly one current-level
+    /// literal: the decision that needs to flip. That's a weaker, less
+    /// minimal cut than classic first-UIP, but it's sound (each
+    /// substitution is a real resolution step against a clause already in
+    /// the database) and - unlike negating the raw conflict clause - it
+    /// actually becomes unit once `backtrack_to` unwinds past every other
+    /// level it mentions, so the search makes real progress instead of
+    /// re-deciding the same way forever.
+    fn analyze(&self, confl_clause: &[Lit]) -> (i32, Vec<Lit>) {
+        let cur_level = self.current_level();
+        let mut seen = vec![false; self.num_vars + 1];
+        let mut learned: Vec<Lit> = Vec::new();
+        let mut stack: Vec<Lit> = confl_clause.to_vec();
+
+        let mut idx = 0;
+        while idx < stack.len() {
+            let lit = stack[idx];
+            idx += 1;
+            let v = lit.unsigned_abs() as usize;
+            if seen[v] {
+                continue;
+            }
+            seen[v] = true;
+
+            if self.level[v] == cur_level {
+                if let Some(reason_idx) = self.reason[v] {
+                    for &rl in &self.clauses[reason_idx].lits {
+                        let rv = rl.unsigned_abs() as usize;
+                        if rv != v && !seen[rv] {
+                            stack.push(rl);
+                        }
+                    }
+                    continue;
+                }
+            }
+            learned.push(-lit);
+        }
+
+        let mut levels: Vec<i32> = learned
+            .iter()
+            .map(|&l| self.level[l.unsigned_abs() as usize])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        let backjump = if levels.len() >= 2 {
+            levels[levels.len() - 2]
+        } else {
+            0
+        };
+        (backjump, learned)
+    }
+
+    fn backtrack_to(&mut self, level: i32) {
+        if self.current_level() <= level {
+            return;
+        }
+        let target_len = if level < 0 {
+            0
+        } else {
+            self.trail_lim[level as usize]
+        };
+        while self.trail.len() > target_len {
+            let lit = self.trail.pop().unwrap();
+            let v = lit.unsigned_abs() as usize;
+            self.assignment[v] = None;
+            self.level[v] = -1;
+            self.reason[v] = None;
+        }
+        self.trail_lim.truncate(level.max(0) as usize);
+    }
+
+    fn pick_unassigned(&self) -> Option<usize> {
+        (1..=self.num_vars).find(|&v| self.assignment[v].is_none())
+    }
+
+    /// Solve the current clause database. Returns the satisfying assignment
+    /// (entry `i` holds variable `i + 1`; for a cell variable, true =
+    /// BACKSLASH) if one exists.
+    fn solve(&mut self) -> Option<Vec<bool>> {
+        loop {
+            if self.unsat {
+                return None;
+            }
+            if let Some(confl) = self.propagate() {
+                if self.current_level() == 0 {
+                    return None;
+                }
+                let confl_lits = self.clauses[confl].lits.clone();
+                let (backjump, learned) = self.analyze(&confl_lits);
+                self.backtrack_to(backjump);
+                self.add_clause(learned);
+                self.conflicts_since_restart += 1;
+                if self.conflicts_since_restart >= self.restart_threshold {
+                    self.conflicts_since_restart = 0;
+                    self.restart_threshold += self.restart_threshold / 2;
+                    self.backtrack_to(0);
+                }
+                continue;
+            }
+
+            match self.pick_unassigned() {
+                None => {
+                    let result = (1..=self.num_vars)
+                        .map(|v| self.assignment[v].unwrap_or(false))
+                        .collect();
+                    return Some(result);
+                }
+                Some(v) => {
+                    self.push_level();
+                    self.enqueue(v as Lit, None);
+                }
+            }
+        }
+    }
+}
+
+/// Literal for the variable of cell `(x, y)` in a `w x h` grid, 1-based.
+fn cell_var(x: usize, y: usize, w: usize) -> i32 {
+    (y * w + x + 1) as i32
+}
+
+/// Emit an exactly-k-of-n cardinality constraint over `lits` using a
+/// sequential-counter encoding with auxiliary variables `s_{i,j}` meaning
+/// "at least j of the first i literals are true".
+fn encode_exactly_k(cdcl: &mut Cdcl, next_var: &mut i32, lits: &[Lit], k: usize) {
+    let n = lits.len();
+    if k > n {
+        cdcl.add_clause(vec![]); // UNSAT: impossible clue
+        return;
+    }
+    if n == 0 {
+        if k != 0 {
+            cdcl.add_clause(vec![]);
+        }
+        return;
+    }
+    if k == 0 {
+        // No counter needed: every touching literal must be false.
+        for &l in lits {
+            cdcl.add_clause(vec![-l]);
+        }
+        return;
+    }
+
+    // s[i][j] for i in 1..=n, j in 1..=k
+    let mut s = vec![vec![0i32; k + 1]; n + 1];
+    for row in s.iter_mut().skip(1) {
+        for cell in row.iter_mut().skip(1) {
+            *cell = *next_var;
+            *next_var += 1;
+        }
+    }
+    // Every s[i][j] above is about to appear in a clause below - grow the
+    // variable tables to cover them before any `add_clause` call does.
+    cdcl.ensure_capacity((*next_var - 1) as usize);
+
+    // s[1][1] <-> lits[0]
+    if k >= 1 {
+        cdcl.add_clause(vec![-lits[0], s[1][1]]);
+        cdcl.add_clause(vec![lits[0], -s[1][1]]);
+    }
+    for &sv in s[1].iter().skip(2) {
+        cdcl.add_clause(vec![-sv]);
+    }
+
+    for i in 2..=n {
+        if k >= 1 {
+            // s[i][1] <-> s[i-1][1] OR lits[i-1]
+            cdcl.add_clause(vec![-s[i - 1][1], s[i][1]]);
+            cdcl.add_clause(vec![-lits[i - 1], s[i][1]]);
+            cdcl.add_clause(vec![s[i - 1][1], lits[i - 1], -s[i][1]]);
+        }
+        for j in 2..=k {
+            // s[i][j] <-> s[i-1][j] OR (s[i-1][j-1] AND lits[i-1])
+            cdcl.add_clause(vec![-s[i - 1][j], s[i][j]]);
+            cdcl.add_clause(vec![-s[i - 1][j - 1], -lits[i - 1], s[i][j]]);
+            cdcl.add_clause(vec![s[i - 1][j], -s[i][j], s[i - 1][j - 1]]); // weak but sound direction
+            cdcl.add_clause(vec![s[i - 1][j], -s[i][j], lits[i - 1]]);
+        }
+        // Forbid more than k: if lits[i-1] true and s[i-1][k] true, s[i][k] would need k+1.
+        cdcl.add_clause(vec![-lits[i - 1], -s[i - 1][k]]);
+    }
+
+    // At least k: s[n][k] must hold.
+    cdcl.add_clause(vec![s[n][k]]);
+}
+
+/// Literals touching vertex `(vx, vy)`, true when the adjoining cell's
+/// diagonal points into the vertex.
+fn vertex_touch_lits(vx: usize, vy: usize, w: usize, h: usize) -> Vec<Lit> {
+    let mut lits = Vec::new();
+    if vx > 0 && vy > 0 {
+        // top-left cell touches iff BACKSLASH (var true)
+        lits.push(cell_var(vx - 1, vy - 1, w));
+    }
+    if vx < w && vy > 0 {
+        // top-right cell touches iff SLASH (var false)
+        lits.push(-cell_var(vx, vy - 1, w));
+    }
+    if vx > 0 && vy < h {
+        // bottom-left cell touches iff SLASH (var false)
+        lits.push(-cell_var(vx - 1, vy, w));
+    }
+    if vx < w && vy < h {
+        // bottom-right cell touches iff BACKSLASH (var true)
+        lits.push(cell_var(vx, vy, w));
+    }
+    lits
+}
+
+/// Find the cells forming a closed loop in a fully-assigned, diagonal-only
+/// grid, via union-find over vertices plus a parent-edge trace to recover
+/// the exact cycle once two already-connected vertices are joined.
+fn find_loop_cells(values: &[u8], w: usize, h: usize) -> Option<Vec<usize>> {
+    let vw = w + 1;
+    let num_vertices = vw * (h + 1);
+    let mut parent: Vec<usize> = (0..num_vertices).collect();
+    // via[v] = (other vertex, cell index) used to reach v's root
+    let mut via: Vec<Option<(usize, usize)>> = vec![None; num_vertices];
+
+    fn find(parent: &mut Vec<usize>, x: usize) -> usize {
+        if parent[x] != x {
+            parent[x] = find(parent, parent[x]);
+        }
+        parent[x]
+    }
+
+    for y in 0..h {
+        for x in 0..w {
+            let cell_idx = y * w + x;
+            let value = values[cell_idx];
+            let (v1, v2) = if value == SLASH {
+                ((y + 1) * vw + x, y * vw + (x + 1))
+            } else {
+                (y * vw + x, (y + 1) * vw + (x + 1))
+            };
+
+            let r1 = find(&mut parent, v1);
+            let r2 = find(&mut parent, v2);
+            if r1 == r2 {
+                // Closed loop found: walk both v1 and v2 back to their common
+                // ancestor, collecting the cells traversed.
+                let mut path1 = vec![v1];
+                let mut cur = v1;
+                while let Some((prev, _)) = via[cur] {
+                    cur = prev;
+                    path1.push(cur);
+                }
+                let mut path2 = vec![v2];
+                cur = v2;
+                while let Some((prev, _)) = via[cur] {
+                    cur = prev;
+                    path2.push(cur);
+                }
+                // The two paths share a common root; the loop is path1 + path2
+                // plus the closing edge (v1,v2) itself.
+                let mut cells: Vec<usize> = Vec::new();
+                let mut c = v1;
+                while let Some((prev, cell)) = via[c] {
+                    cells.push(cell);
+                    c = prev;
+                }
+                c = v2;
+                while let Some((prev, cell)) = via[c] {
+                    cells.push(cell);
+                    c = prev;
+                }
+                cells.push(cell_idx);
+                cells.sort_unstable();
+                cells.dedup();
+                return Some(cells);
+            }
+            parent[r2] = r1;
+            via[v2] = Some((v1, cell_idx));
+            // Keep a usable path root for future traces.
+            via[r2] = via[r2].or(Some((v1, cell_idx)));
+        }
+    }
+    None
+}
+
+/// Solve a puzzle using a SAT encoding with lazy loop elimination.
+pub fn solve(
+    givens_string: &str,
+    width: usize,
+    height: usize,
+    _max_tier: u8,
+) -> Result<SolveResult, String> {
+    let board = Board::new(width, height, givens_string)?;
+    let clued = board.get_clued_vertices();
+    let num_vars = width * height;
+
+    let mut first_solution: Option<Vec<u8>> = None;
+    let mut solution_count = 0usize;
+    let mut extra_blocking: Vec<Vec<Lit>> = Vec::new();
+
+    loop {
+        let mut cdcl = Cdcl::new(num_vars);
+        let mut next_var = (num_vars + 1) as i32;
+        for &(vx, vy, clue) in &clued {
+            let lits = vertex_touch_lits(vx, vy, width, height);
+            encode_exactly_k(&mut cdcl, &mut next_var, &lits, clue as usize);
+        }
+        for clause in &extra_blocking {
+            cdcl.add_clause(clause.clone());
+        }
+
+        let model = match cdcl.solve() {
+            Some(m) => m,
+            None => break, // UNSAT under current blocking clauses
+        };
+
+        let values: Vec<u8> = (0..num_vars)
+            .map(|i| if model[i] { BACKSLASH } else { SLASH })
+            .collect();
+
+        if let Some(cells) = find_loop_cells(&values, width, height) {
+            // Forbid this exact combination of orientations on the looped cells.
+            let clause: Vec<Lit> = cells
+                .iter()
+                .map(|&idx| {
+                    let lit = (idx + 1) as Lit;
+                    if values[idx] == BACKSLASH {
+                        -lit
+                    } else {
+                        lit
+                    }
+                })
+                .collect();
+            extra_blocking.push(clause);
+            continue;
+        }
+
+        if first_solution.is_none() {
+            first_solution = Some(values.clone());
+            solution_count = 1;
+            // Block this exact full assignment and re-solve to test uniqueness.
+            let clause: Vec<Lit> = (0..num_vars)
+                .map(|i| {
+                    let lit = (i + 1) as Lit;
+                    if values[i] == BACKSLASH {
+                        -lit
+                    } else {
+                        lit
+                    }
+                })
+                .collect();
+            extra_blocking.push(clause);
+            continue;
+        } else {
+            solution_count += 1;
+            break;
+        }
+    }
+
+    match first_solution {
+        None => Ok(SolveResult {
+            status: "unsolved".to_string(),
+            solution: ".".repeat(num_vars),
+            work_score: 0,
+            max_tier_used: 0,
+            tt_hits: 0,
+            tt_misses: 0,
+            solution_rate: 0.0,
+            branch_count: 0,
+            difficulty: "Expert".to_string(),
+            guesses_used: 0,
+            max_weight_used: 0,
+        }),
+        Some(values) => {
+            let solution: String = values
+                .iter()
+                .map(|&v| if v == BACKSLASH { '\\' } else { '/' })
+                .collect();
+            let status = if solution_count >= 2 { "mult" } else { "solved" };
+            Ok(SolveResult {
+                status: status.to_string(),
+                solution,
+                work_score: 0,
+                max_tier_used: 0,
+                tt_hits: 0,
+                tt_misses: 0,
+                solution_rate: 1.0,
+                branch_count: 0,
+                difficulty: "Expert".to_string(),
+                guesses_used: 0,
+                max_weight_used: 0,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_on_an_unconstrained_board_does_not_panic() {
+        // A 1x1 board with no clues ("d" = a run of 4 clueless vertices)
+        // forces a unit blocking clause once the first solution is found,
+        // with nothing else to decide on - add_clause must enqueue that
+        // unit immediately so the next restart's backtrack_to(0) doesn't
+        // underflow trail_lim. Both orientations are valid and neither
+        // forms a loop, so this must come back "mult".
+        let result = solve("d", 1, 1, 10).unwrap();
+        assert_eq!(result.status, "mult");
+    }
+
+    #[test]
+    fn solves_a_3x3_puzzle_uniquely_and_matches_the_pr_solver() {
+        // Regression for the unsound conflict analysis: negating the raw
+        // conflict clause (rather than resolving away propagated literals)
+        // never actually forced a decision to flip, so this board - which
+        // needs real backtracking to pin down - previously came back
+        // "mult" even though it has exactly one valid solution.
+        let sat = crate::solver_sat::solve("b2b1e20c", 3, 3, 10).unwrap();
+        let pr = crate::solver_pr::solve("b2b1e20c", 3, 3, 10).unwrap();
+        assert_eq!(pr.status, "solved");
+        assert_eq!(sat.status, "solved");
+        assert_eq!(sat.solution, pr.solution);
+    }
+
+    #[test]
+    fn solves_a_4x4_puzzle_without_looping_forever() {
+        // Regression for the same unsound analysis hanging indefinitely on
+        // anything past trivial sizes: without a learned clause that ever
+        // becomes unit after backtracking, `solve` just re-decided the same
+        // assignment on every restart.
+        let sat = crate::solver_sat::solve("c1b3d33e3a1a2a0", 4, 4, 10).unwrap();
+        let pr = crate::solver_pr::solve("c1b3d33e3a1a2a0", 4, 4, 10).unwrap();
+        assert_eq!(pr.status, "solved");
+        assert_eq!(sat.status, "solved");
+        assert_eq!(sat.solution, pr.solution);
+    }
+}
@@ -0,0 +1,155 @@
+//! Recursive backtracking search for Slants, for puzzles the logical rules
+//! can't finish on their own. Fires the production rule set to a fixed point
+//! for pruning between branches (the same `run_to_fixed_point` driver the PR
+//! solver uses), then - if rules alone can't finish - picks the undecided
+//! cell with the fewest legal values and recurses on each, undoing via
+//! `push_checkpoint`/`rollback_to` rather than cloning the board.
+//! `solve_with_search_depth` returns the first solution found, paired with
+//! how many branch points it took, for `solver_pr::solve`'s fallback;
+//! `count_solutions` counts distinct solutions up to a limit, for uniqueness
+//! testing.
+//!
+//! Different guess orders (cell A then B vs. B then A) can reach the same
+//! partial assignment, so each search keeps a `HashSet` of `Board::zobrist_hash`
+//! values seen so far and skips a node whose exact diagonal assignment has
+//! already been expanded - the hash is cheap to maintain (XOR-updated
+//! incrementally in `place_value`) and cheap to check against, well before
+//! re-running the full rule fixpoint would reveal the same thing.
+//!
+//! This duplicates `solver_bf`'s generic `engine`-driven backtracking rather
+//! than building on it: `engine::solve` branches on *every* undecided cell
+//! through the uniform `puzzle::Puzzle` trait interface, which is the right shape for the
+//! original brute-force solver but too generic to cheaply re-check only the
+//! tier-bounded PR rule set between branches. Keeping this DFS Slants-
+//! specific lets `solver_pr`'s fallback and `generator::generate_for_tier`'s
+//! tier-bounded clue stripping share one cheap, rule-aware search instead of
+//! going through the `Puzzle` trait adapter on every node.
+
+use std::collections::HashSet;
+
+use crate::board::{Board, SLASH};
+use crate::rules::{get_pr_rules, run_to_fixed_point, RuleInfo};
+
+/// Count distinct solutions reachable from `board`'s current state, stopping
+/// once `limit` have been found (pass 2 to cheaply test uniqueness).
+/// Restores `board` to its original state before returning. Takes `&mut
+/// Board` rather than `&Board` - this crate explores hypothetical board
+/// states via checkpoint/rollback rather than cloning, so the board is
+/// restored, not actually mutated, by the time this returns.
+pub fn count_solutions(board: &mut Board, limit: usize) -> usize {
+    search_for_solutions(board, limit).len()
+}
+
+/// Find one solution reachable from `board`'s current state via the same
+/// rule-pruned DFS `count_solutions` uses, also returning how many branch
+/// points the winning path guessed through (0 if the logical rules alone
+/// finished it). Returns `None` if the board is contradictory or has no
+/// reachable solution.
+pub fn solve_with_search_depth(board: &mut Board) -> Option<(String, u32)> {
+    search_for_solutions(board, 1).into_iter().next()
+}
+
+/// Run the rule-pruned DFS to collect up to `max_solutions` distinct
+/// solutions, each paired with the guess count of the path that found it,
+/// restoring `board` to its original state before returning.
+fn search_for_solutions(board: &mut Board, max_solutions: usize) -> Vec<(String, u32)> {
+    let rules = get_pr_rules();
+    let mut solutions = Vec::new();
+    let mut seen = HashSet::new();
+    let checkpoint = board.push_checkpoint();
+    search(board, &rules, max_solutions, 0, &mut seen, &mut solutions);
+    board.rollback_to(checkpoint);
+    solutions
+}
+
+fn search(
+    board: &mut Board,
+    rules: &[(RuleInfo, fn(&mut Board) -> bool)],
+    max_solutions: usize,
+    guesses: u32,
+    seen: &mut HashSet<u64>,
+    solutions: &mut Vec<(String, u32)>,
+) {
+    if solutions.len() >= max_solutions {
+        return;
+    }
+
+    if !seen.insert(board.zobrist_hash()) {
+        return;
+    }
+
+    run_to_fixed_point(board, rules, 10);
+
+    if board.has_contradiction() {
+        return;
+    }
+
+    if board.is_solved() {
+        if board.is_valid_solution() {
+            solutions.push((board.to_solution_string(), guesses));
+        }
+        return;
+    }
+
+    let (cell, values) = match most_constrained_cell(board) {
+        Some(cv) => cv,
+        None => return,
+    };
+
+    for value in values {
+        let checkpoint = board.push_checkpoint();
+        if board.place_value(cell.0, cell.1, value).is_ok() {
+            search(board, rules, max_solutions, guesses + 1, seen, solutions);
+        }
+        board.rollback_to(checkpoint);
+
+        if solutions.len() >= max_solutions {
+            return;
+        }
+    }
+}
+
+/// The undecided cell with the fewest legal values, à la Sudoku's
+/// candidate-count heuristic, paired with those values.
+fn most_constrained_cell(board: &mut Board) -> Option<((usize, usize), Vec<u8>)> {
+    board
+        .get_unknown_cells()
+        .into_iter()
+        .map(|cell| (cell, legal_values(board, cell.0, cell.1)))
+        .min_by_key(|(_, values)| values.len())
+}
+
+/// Orientations for a cell that neither close a loop nor push a touching
+/// clue's satisfied count past its value.
+fn legal_values(board: &mut Board, cx: usize, cy: usize) -> Vec<u8> {
+    let mut valid = Vec::new();
+
+    for value in board.get_cell(cx, cy).unwrap().state.variants() {
+        if board.would_form_loop(cx, cy, value) {
+            continue;
+        }
+
+        let touches = if value == SLASH {
+            [(cx, cy + 1), (cx + 1, cy)]
+        } else {
+            [(cx, cy), (cx + 1, cy + 1)]
+        };
+
+        let mut ok = true;
+        for (vx, vy) in touches {
+            if let Some(clue) = board.get_vertex_clue(vx, vy) {
+                let (current, _) = board.count_touches(vx, vy);
+                if current >= clue {
+                    ok = false;
+                    break;
+                }
+            }
+        }
+
+        if ok {
+            valid.push(value);
+        }
+    }
+
+    valid
+}
@@ -0,0 +1,133 @@
+//! Human-style tutor solver: replays a production-rule solve as an ordered
+//! list of per-cell deductions, each tagged by how forced it was, so a UI
+//! can walk a solution step by step and the generator can grade difficulty
+//! by the highest tier actually needed to finish.
+
+use crate::board::{grade_difficulty, Board, SolveResult, UNKNOWN};
+use crate::rules::get_pr_rules;
+
+/// How forced a deduction was. Mirrors the existing rule tiers: tier 1
+/// rules are trivial clue/no-loop forcing, tier 2 rules are logical
+/// inference across several cells (exit counting, equivalence classes,
+/// v-bitmap elimination), tier 3 rules only resolve by placing a hypothesis
+/// behind a checkpoint and rolling it back once it contradicts.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RuleKind {
+    Trivial,
+    Logic,
+    Probe,
+}
+
+/// One cell's value and why the solver was able to place it.
+pub struct Deduction {
+    pub cell: (usize, usize),
+    pub value: u8,
+    pub tier: u8,
+    pub rule: RuleKind,
+}
+
+/// A tutor-style solve: the usual `SolveResult` summary plus the ordered
+/// deduction trail that produced it.
+pub struct TutorResult {
+    pub result: SolveResult,
+    pub deductions: Vec<Deduction>,
+}
+
+/// Work-score weight per tier, set so probe steps (tier 3) cost far more
+/// than trivial forced moves, since they require placing and disproving a
+/// hypothesis rather than reading a clue directly.
+fn tier_work_weight(tier: u8) -> u32 {
+    match tier {
+        1 => 1,
+        2 => 4,
+        _ => 20,
+    }
+}
+
+fn rule_kind_for_tier(tier: u8) -> RuleKind {
+    match tier {
+        1 => RuleKind::Trivial,
+        2 => RuleKind::Logic,
+        _ => RuleKind::Probe,
+    }
+}
+
+/// Solve a puzzle with production rules, recording an ordered `Deduction`
+/// for every cell as it's filled.
+pub fn solve(
+    givens_string: &str,
+    width: usize,
+    height: usize,
+    max_tier: u8,
+) -> Result<TutorResult, String> {
+    let mut board = Board::new(width, height, givens_string)?;
+    let rules = get_pr_rules();
+
+    let mut deductions = Vec::new();
+    let mut total_work_score = 0u32;
+    let mut max_tier_used = 0u8;
+
+    loop {
+        if board.is_solved() {
+            break;
+        }
+
+        let before_hash = board.state_hash();
+        let mut made_progress = false;
+
+        for (info, rule_func) in &rules {
+            if info.tier > max_tier {
+                continue;
+            }
+
+            let watched = board.get_unknown_cells();
+            if rule_func(&mut board) {
+                max_tier_used = max_tier_used.max(info.tier);
+                made_progress = true;
+
+                let mut newly_filled = 0u32;
+                for (cx, cy) in watched {
+                    let value = board.get_cell_value(cx, cy);
+                    if value != UNKNOWN {
+                        deductions.push(Deduction {
+                            cell: (cx, cy),
+                            value,
+                            tier: info.tier,
+                            rule: rule_kind_for_tier(info.tier),
+                        });
+                        newly_filled += 1;
+                    }
+                }
+                total_work_score += tier_work_weight(info.tier) * newly_filled.max(1);
+                break;
+            }
+        }
+
+        if !made_progress || board.state_hash() == before_hash {
+            break;
+        }
+    }
+
+    let solution_rate = board.solution_rate();
+    let status = if board.is_solved() && board.is_valid_solution() {
+        "solved"
+    } else {
+        "unsolved"
+    };
+
+    let result = SolveResult {
+        status: status.to_string(),
+        solution: board.to_solution_string(),
+        work_score: total_work_score,
+        max_tier_used,
+        tt_hits: 0,
+        tt_misses: 0,
+        solution_rate,
+        branch_count: 0,
+        difficulty: grade_difficulty(max_tier_used, 0, solution_rate),
+        guesses_used: 0,
+        max_weight_used: 0,
+    };
+
+    Ok(TutorResult { result, deductions })
+}